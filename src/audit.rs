@@ -0,0 +1,135 @@
+use std::io::{self, Write};
+
+use crate::word_list::WordList;
+
+/// Which step of the correction pipeline decided a word's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Empty tokens are returned as they are.
+    Empty,
+    /// Left alone by a heuristic: numbers, acronyms, symbol-heavy tokens.
+    Skipped,
+    /// Found in the secondary dictionary of accepted words.
+    Accepted,
+    /// A forced or learned correction.
+    Forced,
+    /// Already in the dictionary, possibly up to case.
+    Known,
+    /// Unknown, but the checker only checks membership.
+    CheckOnly,
+    /// A dictionary entry after shortening repeated letters ("soooo").
+    Collapsed,
+    /// A dictionary entry with the same stem.
+    Stem,
+    /// The nearest candidate, ties settled by a frequency list.
+    Frequency,
+    /// The nearest candidate by edit distance.
+    Nearest,
+    /// A candidate with the same phonetic key.
+    Phonetic,
+    /// Unknown, with no candidate within the distance limit.
+    Kept,
+    /// An inline hint given in the input.
+    Hint,
+    /// Split into several dictionary words.
+    Segmented,
+    /// Unknown and removed from the line, leaving an empty output.
+    Dropped,
+    /// Unknown, but left alone because the line had used up its corrections.
+    Limited,
+    /// Unknown, but left alone because the line was clean enough to skip.
+    Clean,
+}
+
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Empty => "empty",
+            Strategy::Skipped => "skipped",
+            Strategy::Accepted => "accepted",
+            Strategy::Forced => "forced",
+            Strategy::Known => "known",
+            Strategy::CheckOnly => "check-only",
+            Strategy::Collapsed => "collapsed",
+            Strategy::Stem => "stem",
+            Strategy::Frequency => "frequency",
+            Strategy::Nearest => "nearest",
+            Strategy::Phonetic => "phonetic",
+            Strategy::Kept => "kept",
+            Strategy::Hint => "hint",
+            Strategy::Segmented => "segmented",
+            Strategy::Dropped => "dropped",
+            Strategy::Limited => "limited",
+            Strategy::Clean => "clean",
+        }
+    }
+}
+
+/// How a single word was corrected, as returned by
+/// [`SpellChecker::decide`](crate::SpellChecker::decide) and
+/// [`SpellChecker::decide_word_list`](crate::SpellChecker::decide_word_list).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub input: String,
+    pub output: String,
+    /// Edit distance from `input` to `output`.
+    pub distance: usize,
+    /// [`SpellChecker::score`](crate::SpellChecker::score) of `output`.
+    pub confidence: f64,
+    /// Candidates the search considered; 0 when no search was needed.
+    pub candidates: usize,
+    pub strategy: Strategy,
+}
+
+impl Decision {
+    /// One JSON object on a single line, tagged with the entry's ID and
+    /// input line number.
+    pub fn to_json(&self, id: &str, line_number: usize) -> String {
+        format!(
+            "{{\"id\":{},\"line\":{},\"input\":{},\"output\":{},\"distance\":{},\"confidence\":{},\"candidates\":{},\"strategy\":\"{}\"}}",
+            json_string(id),
+            line_number,
+            json_string(&self.input),
+            json_string(&self.output),
+            self.distance,
+            self.confidence,
+            self.candidates,
+            self.strategy.name()
+        )
+    }
+}
+
+/// Writes one JSON line per decision for `word_list` and flushes, so the
+/// log is complete up to the last entry even if the run stops early.
+pub fn write_audit<W: Write>(
+    out: &mut W,
+    word_list: &WordList,
+    decisions: &[Decision],
+) -> io::Result<()> {
+    for decision in decisions {
+        writeln!(
+            out,
+            "{}",
+            decision.to_json(&word_list.id, word_list.line_number)
+        )?;
+    }
+    out.flush()
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,31 @@
+/// Strategy for narrowing the dictionary down to the entries worth a
+/// distance computation. The checker still measures every candidate and
+/// applies its own filters, so a source only has to be conservative: it may
+/// return entries that turn out too far away, but must never leave out one
+/// within `max_distance`.
+pub trait CandidateSource: Send + Sync {
+    /// Called with the sorted dictionary before the first lookup, and again
+    /// whenever the source is attached to a checker, so it can build an index.
+    fn build(&mut self, _dictionary: &[String]) {}
+
+    /// Indices into `dictionary` of the candidates for `word`, in ascending
+    /// order so ties keep resolving to the alphabetically first entry.
+    /// `max_distance` is `usize::MAX` when no threshold is set.
+    fn candidates(&self, dictionary: &[String], word: &str, max_distance: usize) -> Vec<usize>;
+
+    /// Estimated heap bytes used by the source's index, for
+    /// [`SpellChecker::memory_report`](crate::SpellChecker::memory_report).
+    fn heap_bytes(&self) -> usize {
+        0
+    }
+}
+
+/// Every dictionary entry is a candidate. Needs no index.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearScan;
+
+impl CandidateSource for LinearScan {
+    fn candidates(&self, dictionary: &[String], _word: &str, _max_distance: usize) -> Vec<usize> {
+        (0..dictionary.len()).collect()
+    }
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+/// Combining mark -> pairs of a base letter and the precomposed letter it
+/// forms with that mark, covering the Latin blocks up to Latin Extended
+/// Additional.
+const COMPOSITIONS: &[(char, &str)] = &[
+    // Grave Accent
+    (
+        '\u{300}',
+        concat!(
+            "AÀEÈIÌNǸOÒUÙWẀYỲaàeèiìnǹoòuùwẁyỳÂẦÊỀÔỒÜǛ",
+            "âầêềôồüǜĂẰăằĒḔēḕŌṐōṑƠỜơờƯỪưừ",
+        ),
+    ),
+    // Acute Accent
+    (
+        '\u{301}',
+        concat!(
+            "AÁCĆEÉGǴIÍKḰLĹMḾNŃOÓPṔRŔSŚUÚWẂYÝZŹaácćeé",
+            "gǵiíkḱlĺmḿnńoópṕrŕsśuúwẃyýzźÂẤÅǺÆǼÇḈÊẾÏḮ",
+            "ÔỐÕṌØǾÜǗâấåǻæǽçḉêếïḯôốõṍøǿüǘĂẮăắĒḖēḗŌṒōṓ",
+            "ŨṸũṹƠỚơớƯỨưứ",
+        ),
+    ),
+    // Circumflex Accent
+    (
+        '\u{302}',
+        concat!(
+            "AÂCĈEÊGĜHĤIÎJĴOÔSŜUÛWŴYŶZẐaâcĉeêgĝhĥiîjĵ",
+            "oôsŝuûwŵyŷzẑẠẬạậẸỆẹệỌỘọộ",
+        ),
+    ),
+    // Tilde
+    (
+        '\u{303}',
+        concat!(
+            "AÃEẼIĨNÑOÕUŨVṼYỸaãeẽiĩnñoõuũvṽyỹÂẪÊỄÔỖâẫ",
+            "êễôỗĂẴăẵƠỠơỡƯỮưữ",
+        ),
+    ),
+    // Macron
+    (
+        '\u{304}',
+        concat!(
+            "AĀEĒGḠIĪOŌUŪYȲaāeēgḡiīoōuūyȳÄǞÆǢÕȬÖȪÜǕäǟ",
+            "æǣõȭöȫüǖǪǬǫǭȦǠȧǡȮȰȯȱḶḸḷḹṚṜṛṝ",
+        ),
+    ),
+    // Breve
+    ('\u{306}', "AĂEĔGĞIĬOŎUŬaăeĕgğiĭoŏuŭȨḜȩḝẠẶạặ"),
+    // Dot Above
+    (
+        '\u{307}',
+        concat!(
+            "AȦBḂCĊDḊEĖFḞGĠHḢIİMṀNṄOȮPṖRṘSṠTṪWẆXẊYẎZŻ",
+            "aȧbḃcċdḋeėfḟgġhḣmṁnṅoȯpṗrṙsṡtṫwẇxẋyẏzżŚṤ",
+            "śṥŠṦšṧſẛṢṨṣṩ",
+        ),
+    ),
+    // Diaeresis
+    (
+        '\u{308}',
+        concat!("AÄEËHḦIÏOÖUÜWẄXẌYŸaäeëhḧiïoötẗuüwẅxẍyÿÕṎ", "õṏŪṺūṻ",),
+    ),
+    // Hook Above
+    (
+        '\u{309}',
+        concat!("AẢEẺIỈOỎUỦYỶaảeẻiỉoỏuủyỷÂẨÊỂÔỔâẩêểôổĂẲăẳ", "ƠỞơởƯỬưử",),
+    ),
+    // Ring Above
+    ('\u{30a}', "AÅUŮaåuůwẘyẙ"),
+    // Double Acute Accent
+    ('\u{30b}', "OŐUŰoőuű"),
+    // Caron
+    (
+        '\u{30c}',
+        concat!(
+            "AǍCČDĎEĚGǦHȞIǏKǨLĽNŇOǑRŘSŠTŤUǓZŽaǎcčdďeě",
+            "gǧhȟiǐjǰkǩlľnňoǒrřsštťuǔzžÜǙüǚƷǮʒǯ",
+        ),
+    ),
+    // Double Grave Accent
+    ('\u{30f}', "AȀEȄIȈOȌRȐUȔaȁeȅiȉoȍrȑuȕ"),
+    // Inverted Breve
+    ('\u{311}', "AȂEȆIȊOȎRȒUȖaȃeȇiȋoȏrȓuȗ"),
+    // Horn
+    ('\u{31b}', "OƠUƯoơuư"),
+    // Dot Below
+    (
+        '\u{323}',
+        concat!(
+            "AẠBḄDḌEẸHḤIỊKḲLḶMṂNṆOỌRṚSṢTṬUỤVṾWẈYỴZẒaạ",
+            "bḅdḍeẹhḥiịkḳlḷmṃnṇoọrṛsṣtṭuụvṿwẉyỵzẓƠỢơợ",
+            "ƯỰưự",
+        ),
+    ),
+    // Diaeresis Below
+    ('\u{324}', "UṲuṳ"),
+    // Ring Below
+    ('\u{325}', "AḀaḁ"),
+    // Comma Below
+    ('\u{326}', "SȘTȚsștț"),
+    // Cedilla
+    (
+        '\u{327}',
+        concat!("CÇDḐEȨGĢHḨKĶLĻNŅRŖSŞTŢcçdḑeȩgģhḩkķlļnņrŗ", "sştţ",),
+    ),
+    // Ogonek
+    ('\u{328}', "AĄEĘIĮOǪUŲaąeęiįoǫuų"),
+    // Circumflex Accent Below
+    ('\u{32d}', "DḒEḘLḼNṊTṰUṶdḓeḙlḽnṋtṱuṷ"),
+    // Breve Below
+    ('\u{32e}', "HḪhḫ"),
+    // Tilde Below
+    ('\u{330}', "EḚIḬUṴeḛiḭuṵ"),
+    // Macron Below
+    ('\u{331}', "BḆDḎKḴLḺNṈRṞTṮZẔbḇdḏhẖkḵlḻnṉrṟtṯzẕ"),
+];
+
+/// The precomposed letter for `base` followed by the combining `mark`.
+fn compose_pair(base: char, mark: char) -> Option<char> {
+    let (_, pairs) = COMPOSITIONS.iter().find(|(m, _)| *m == mark)?;
+    let mut chars = pairs.chars();
+    while let (Some(b), Some(composed)) = (chars.next(), chars.next()) {
+        if b == base {
+            return Some(composed);
+        }
+    }
+    None
+}
+
+/// Canonically composes Latin letters written as a base letter plus
+/// combining marks ("e\u{301}" -> "é"), so both spellings of a word look up
+/// and cache the same way. Marks are applied in the order written; text
+/// without combining marks is returned as is.
+pub(crate) fn compose(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| ('\u{300}'..='\u{36f}').contains(&c)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut composed = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some(last) = composed.chars().next_back()
+            && let Some(combined) = compose_pair(last, c)
+        {
+            composed.truncate(composed.len() - last.len_utf8());
+            composed.push(combined);
+        } else {
+            composed.push(c);
+        }
+    }
+    Cow::Owned(composed)
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use crate::spell_checker::SpellChecker;
+use crate::word_list::{Token, WordList};
+
+/// How much of an input text the dictionary already knows.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    pub total_words: usize,
+    pub known_words: usize,
+    unknown: HashMap<String, usize>,
+}
+
+impl CoverageReport {
+    /// Checks every word token against the dictionary without correcting anything.
+    pub fn measure(checker: &SpellChecker, word_lists: &[WordList]) -> Self {
+        let mut report = Self::default();
+
+        for word in word_lists
+            .iter()
+            .flat_map(|word_list| &word_list.tokens)
+            .filter_map(|token| match token {
+                Token::Word(word) => Some(word),
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+        {
+            report.total_words += 1;
+            if checker.contains_word(word) {
+                report.known_words += 1;
+            } else {
+                *report.unknown.entry(word.clone()).or_insert(0) += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Fraction of words found in the dictionary, from 0.0 to 1.0.
+    pub fn coverage(&self) -> f64 {
+        if self.total_words == 0 {
+            0.0
+        } else {
+            self.known_words as f64 / self.total_words as f64
+        }
+    }
+
+    /// The `k` most common out-of-vocabulary words, most common first.
+    pub fn top_unknown(&self, k: usize) -> Vec<(&str, usize)> {
+        let mut words: Vec<(&str, usize)> = self
+            .unknown
+            .iter()
+            .map(|(word, &count)| (word.as_str(), count))
+            .collect();
+
+        words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        words.truncate(k);
+        words
+    }
+}
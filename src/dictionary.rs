@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use std::io::{self, ErrorKind};
+use std::thread;
+
+use strsim::levenshtein;
+
+use crate::compose::compose;
+use crate::encoding::strip_bom;
+use crate::error::SpellError;
+use crate::sanitize::ControlCharPolicy;
+use crate::script::Script;
+use crate::spell_checker::LoadOptions;
+use crate::trace;
+
+/// A loaded word list with its lookup indexes, independent of any
+/// correction settings. Wrap it in an [`Arc`](std::sync::Arc) to share one
+/// copy between several [`SpellChecker`](crate::SpellChecker)s configured
+/// differently.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    /// Sorted and deduplicated.
+    pub(crate) words: Vec<String>,
+    /// Dominant script of each entry, in the same order.
+    pub(crate) scripts: Vec<Script>,
+    /// [`fold`]ed word -> index of its first entry in `words`.
+    pub(crate) folded: HashMap<String, usize>,
+    /// Lines stripped or dropped because of control characters.
+    sanitized: usize,
+    /// Entries containing a separator, kept or dropped.
+    unmatchable: usize,
+}
+
+impl Dictionary {
+    /// Reads the dictionary at `dict_path`, retrying transient failures as
+    /// set in `options`.
+    #[cfg(feature = "fs")]
+    pub fn load<P: AsRef<Path>>(dict_path: P, options: &LoadOptions) -> Result<Self, SpellError> {
+        Self::load_with(|| fs::read_to_string(&dict_path), options)
+    }
+
+    /// Builds a dictionary from the text `read` returns. A read failing with
+    /// an error that may be temporary (interrupted, timed out, locked, or
+    /// cut off mid-character) is retried up to `options.retries` times with
+    /// a growing pause; any other error, or the last one, is reported as
+    /// [`SpellError::Io`]. A read that succeeds but yields no words is
+    /// [`SpellError::EmptyDictionary`] and is not retried.
+    pub fn load_with<F>(mut read: F, options: &LoadOptions) -> Result<Self, SpellError>
+    where
+        F: FnMut() -> io::Result<String>,
+    {
+        let mut attempt = 0;
+        let dict_content = loop {
+            match read() {
+                Ok(content) => break content,
+                Err(e) if attempt < options.retries && is_transient(&e) => {
+                    attempt += 1;
+                    thread::sleep(options.retry_backoff * attempt as u32);
+                }
+                Err(e) if attempt > 0 => {
+                    return Err(SpellError::Io(format!(
+                        "Failed to load dictionary after {} attempts: {}",
+                        attempt + 1,
+                        e
+                    )));
+                }
+                Err(e) => {
+                    return Err(SpellError::Io(format!("Failed to load dictionary: {}", e)));
+                }
+            }
+        };
+        Self::from_content(&dict_content, options)
+    }
+
+    /// Builds a dictionary from text with one word per line, as read by
+    /// [`Dictionary::load`]. Needs no filesystem access.
+    pub fn from_content(dict_content: &str, options: &LoadOptions) -> Result<Self, SpellError> {
+        let mut span = trace::span("load_dictionary");
+        let (content, sanitized) =
+            Self::sanitize_lines(strip_bom(dict_content), options.control_chars);
+
+        let mut words = Self::build_dictionary(&content);
+        let unmatchable = Self::unmatchable(&mut words, options);
+
+        let mut dictionary = Self::from_sorted_words(words)?;
+        dictionary.sanitized = sanitized;
+        dictionary.unmatchable = unmatchable;
+        span.record("words", dictionary.len());
+        span.record("sanitized", sanitized);
+        span.record("unmatchable", unmatchable);
+        Ok(dictionary)
+    }
+
+    /// Builds a dictionary from an in-memory word collection. Words are
+    /// trimmed, control characters stripped, empty entries dropped, and the
+    /// result sorted and deduplicated exactly as when loading from a file.
+    pub fn from_words<I: IntoIterator<Item = String>>(words: I) -> Result<Self, SpellError> {
+        let mut sanitized = 0;
+        let words = words.into_iter().filter_map(|word| {
+            let (word, changed) = ControlCharPolicy::default().apply(&word);
+            sanitized += usize::from(changed);
+            word
+        });
+
+        let mut words = Self::normalize_words(words);
+        let unmatchable = Self::unmatchable(&mut words, &LoadOptions::default());
+
+        let mut dictionary = Self::from_sorted_words(words)?;
+        dictionary.sanitized = sanitized;
+        dictionary.unmatchable = unmatchable;
+        Ok(dictionary)
+    }
+
+    /// Applies `policy` to every line, returning the cleaned content and how
+    /// many lines were stripped or dropped.
+    fn sanitize_lines(content: &str, policy: ControlCharPolicy) -> (String, usize) {
+        let mut sanitized = 0;
+        let mut cleaned = String::with_capacity(content.len());
+
+        for line in content.lines() {
+            let (line, changed) = policy.apply(line.trim());
+            sanitized += usize::from(changed);
+            if let Some(line) = line {
+                cleaned.push_str(&line);
+                cleaned.push('\n');
+            }
+        }
+
+        (cleaned, sanitized)
+    }
+
+    /// Counts the entries containing one of `options.separators`, removing
+    /// them if `options.drop_unmatchable` is set.
+    fn unmatchable(words: &mut Vec<String>, options: &LoadOptions) -> usize {
+        let is_unmatchable = |word: &String| word.contains(options.separators.as_slice());
+        let count = words.iter().filter(|word| is_unmatchable(word)).count();
+        if options.drop_unmatchable {
+            words.retain(|word| !is_unmatchable(word));
+        }
+        count
+    }
+
+    fn from_sorted_words(words: Vec<String>) -> Result<Self, SpellError> {
+        if words.is_empty() {
+            return Err(SpellError::EmptyDictionary);
+        }
+
+        let mut span = trace::span("build_indexes");
+        span.record("words", words.len());
+
+        let mut folded = HashMap::with_capacity(words.len());
+        for (index, word) in words.iter().enumerate() {
+            folded.entry(fold(word)).or_insert(index);
+        }
+
+        let scripts = words.iter().map(|word| Script::dominant(word)).collect();
+
+        Ok(Dictionary {
+            words,
+            scripts,
+            folded,
+            sanitized: 0,
+            unmatchable: 0,
+        })
+    }
+
+    /// Trims, filters, sorts and deduplicates dictionary words.
+    fn normalize_words<I, S>(words: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dictionary: Vec<String> = words
+            .into_iter()
+            .map(|s| s.as_ref().trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        dictionary.sort_unstable();
+        dictionary.dedup();
+        dictionary
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn build_dictionary(content: &str) -> Vec<String> {
+        Self::normalize_words(content.lines())
+    }
+
+    /// Parallel variant of the serial loader: each thread trims and sorts one
+    /// chunk of lines, and the sorted chunks are then merged and deduplicated,
+    /// giving exactly the same dictionary.
+    #[cfg(feature = "parallel")]
+    fn build_dictionary(content: &str) -> Vec<String> {
+        let lines: Vec<&str> = content.lines().collect();
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = lines.len().div_ceil(threads).max(1);
+
+        let chunks: Vec<Vec<String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = lines
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut words: Vec<String> = chunk
+                            .iter()
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect();
+                        words.sort_unstable();
+                        words
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("dictionary loader thread panicked"))
+                .collect()
+        });
+
+        let mut dictionary = chunks
+            .into_iter()
+            .reduce(Self::merge_sorted)
+            .unwrap_or_default();
+        dictionary.dedup();
+        dictionary
+    }
+
+    #[cfg(feature = "parallel")]
+    fn merge_sorted(left: Vec<String>, right: Vec<String>) -> Vec<String> {
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+
+        while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+            if l <= r {
+                merged.extend(left.next());
+            } else {
+                merged.extend(right.next());
+            }
+        }
+        merged.extend(left);
+        merged.extend(right);
+        merged
+    }
+
+    /// Number of entries that contained control characters and were
+    /// stripped or dropped while loading.
+    pub fn sanitized_entries(&self) -> usize {
+        self.sanitized
+    }
+
+    /// Number of entries containing a separator character, which no input
+    /// token can match. Counted whether or not they were dropped.
+    pub fn unmatchable_entries(&self) -> usize {
+        self.unmatchable
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Every entry, in sorted order.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Whether `word` is an entry, spelled exactly.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words
+            .binary_search_by(|entry| entry.as_str().cmp(word))
+            .is_ok()
+    }
+
+    /// The first entry in sorted order whose lowercase form equals `word`'s,
+    /// with composed and decomposed accents treated alike.
+    pub fn folded(&self, word: &str) -> Option<&str> {
+        self.folded
+            .get(&fold(word))
+            .map(|&index| self.words[index].as_str())
+    }
+
+    /// All entries starting with `prefix`, in sorted order. An empty prefix
+    /// returns the whole dictionary.
+    pub fn words_with_prefix(&self, prefix: &str) -> &[String] {
+        let start = self.words.partition_point(|entry| entry.as_str() < prefix);
+        let len = self.words[start..].partition_point(|entry| entry.starts_with(prefix));
+        &self.words[start..start + len]
+    }
+
+    /// Every entry within plain Levenshtein distance `max_distance` of
+    /// `word`, nearest first and alphabetically within a distance. A scan of
+    /// the whole dictionary with none of a checker's settings applied.
+    pub fn candidates(&self, word: &str, max_distance: usize) -> Vec<(&str, usize)> {
+        let mut candidates: Vec<(&str, usize)> = self
+            .words
+            .iter()
+            .map(|entry| (entry.as_str(), levenshtein(word, entry)))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .collect();
+        candidates.sort_by_key(|&(entry, distance)| (distance, entry));
+        candidates
+    }
+}
+
+/// `word` lowercased and canonically composed: the key of case-insensitive
+/// lookups.
+pub(crate) fn fold(word: &str) -> String {
+    compose(&word.to_lowercase()).into_owned()
+}
+
+/// Whether a failed read may succeed if tried again shortly: the file is
+/// locked by another process, or the read was interrupted, timed out or
+/// ended early. Invalid UTF-8 is permanent, since the bytes stay the same.
+fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::TimedOut
+            | ErrorKind::ResourceBusy
+            | ErrorKind::UnexpectedEof
+    ) || (cfg!(windows) && matches!(error.raw_os_error(), Some(32 | 33)))
+}
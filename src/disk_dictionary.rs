@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use strsim::levenshtein;
+
+use crate::encoding::strip_bom;
+use crate::error::SpellError;
+use crate::spell_checker::is_numeric;
+use crate::typography::restore_typography;
+use crate::word_list::{Token, WordList};
+
+/// A dictionary answered straight from a sorted file instead of memory.
+/// Membership is a binary search over byte offsets; corrections stream the
+/// whole file once per unknown word. Only one line is held in memory at a
+/// time, which suits large dictionaries on memory-constrained machines at
+/// the cost of disk reads on every lookup.
+pub struct DiskDictionary {
+    reader: BufReader<File>,
+    /// Offset of the first line, past a byte-order mark if there is one.
+    start: u64,
+    len: u64,
+    max_distance: Option<usize>,
+    ignore_numeric: bool,
+}
+
+fn io_error(e: std::io::Error) -> SpellError {
+    SpellError::Io(format!("Failed to read dictionary: {}", e))
+}
+
+impl DiskDictionary {
+    /// Opens `path`, checking in one streaming pass that its trimmed lines
+    /// are in sorted order, which the binary search relies on. Blank lines
+    /// are ignored, and a leading UTF-8 byte-order mark is skipped, as when
+    /// loading into memory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SpellError> {
+        let file = File::open(path).map_err(io_error)?;
+        let len = file.metadata().map_err(io_error)?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut previous = String::new();
+        let mut line = String::new();
+        let mut line_number = 0;
+        let mut words = 0;
+        let mut start = 0;
+        while reader.read_line(&mut line).map_err(io_error)? > 0 {
+            line_number += 1;
+            let word = if line_number == 1 {
+                let stripped = strip_bom(&line);
+                start = (line.len() - stripped.len()) as u64;
+                stripped.trim()
+            } else {
+                line.trim()
+            };
+            if !word.is_empty() {
+                if word < previous.as_str() {
+                    return Err(SpellError::InvalidLine {
+                        line: line_number,
+                        reason: format!("is out of sorted order: '{}'", word),
+                    });
+                }
+                previous = word.to_string();
+                words += 1;
+            }
+            line.clear();
+        }
+
+        if words == 0 {
+            return Err(SpellError::EmptyDictionary);
+        }
+
+        Ok(DiskDictionary {
+            reader,
+            start,
+            len,
+            max_distance: None,
+            ignore_numeric: true,
+        })
+    }
+
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    /// Leave numeric tokens such as "2024" or "3.14" untouched. On by
+    /// default, as for [`crate::SpellChecker::with_ignore_numeric`].
+    pub fn with_ignore_numeric(mut self, ignore_numeric: bool) -> Self {
+        self.ignore_numeric = ignore_numeric;
+        self
+    }
+
+    /// Reads the line starting at byte `offset`, returning it trimmed along
+    /// with the offset of the following line.
+    fn line_at(&mut self, offset: u64) -> Result<(String, u64), SpellError> {
+        self.reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(io_error)?;
+        let mut line = String::new();
+        let read = self.reader.read_line(&mut line).map_err(io_error)?;
+        Ok((line.trim().to_string(), offset + read as u64))
+    }
+
+    /// Offset of the first line that starts at or after `offset`.
+    fn next_line_start(&mut self, offset: u64) -> Result<u64, SpellError> {
+        if offset <= self.start {
+            return Ok(self.start);
+        }
+        self.reader
+            .seek(SeekFrom::Start(offset - 1))
+            .map_err(io_error)?;
+        let mut skipped = Vec::new();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut skipped)
+            .map_err(io_error)?;
+        Ok(offset - 1 + read as u64)
+    }
+
+    pub fn contains_word(&mut self, word: &str) -> Result<bool, SpellError> {
+        // Search over the line starts in `lo..hi`.
+        let (mut lo, mut hi) = (self.start, self.len);
+
+        'search: while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let mut start = self.next_line_start(mid)?;
+            // Blank lines are allowed between entries; probe the next entry.
+            let (line, end) = loop {
+                if start >= hi {
+                    hi = mid;
+                    continue 'search;
+                }
+                let (line, end) = self.line_at(start)?;
+                if !line.is_empty() {
+                    break (line, end);
+                }
+                start = end;
+            };
+
+            match line.as_str().cmp(word) {
+                std::cmp::Ordering::Equal => return Ok(true),
+                std::cmp::Ordering::Less => lo = end,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Finds the closest entry to `word` by scanning the file, with the same
+    /// early stop at distance 1 as [`crate::SpellChecker::correct_word`].
+    /// Empty and, by default, numeric words are returned unchanged.
+    pub fn correct_word(&mut self, word: &str) -> Result<String, SpellError> {
+        if word.is_empty()
+            || (self.ignore_numeric && is_numeric(word))
+            || self.contains_word(word)?
+        {
+            return Ok(word.to_string());
+        }
+
+        self.reader
+            .seek(SeekFrom::Start(self.start))
+            .map_err(io_error)?;
+        let mut best_match = word.to_string();
+        let mut min_distance = usize::MAX;
+        let mut line = String::new();
+
+        while self.reader.read_line(&mut line).map_err(io_error)? > 0 {
+            let candidate = line.trim();
+            if !candidate.is_empty() {
+                let distance = levenshtein(word, candidate);
+                if distance < min_distance {
+                    min_distance = distance;
+                    best_match = candidate.to_string();
+
+                    if distance <= 1 {
+                        break;
+                    }
+                }
+            }
+            line.clear();
+        }
+
+        if self.max_distance.is_some_and(|max| min_distance > max) {
+            return Ok(word.to_string());
+        }
+
+        Ok(best_match)
+    }
+
+    pub fn correct_word_list(&mut self, word_list: &WordList) -> Result<WordList, SpellError> {
+        let mut tokens = Vec::with_capacity(word_list.tokens.len());
+        for (index, token) in word_list.tokens.iter().enumerate() {
+            tokens.push(match token {
+                Token::Word(_) if word_list.hints.contains_key(&index) => {
+                    Token::Word(word_list.hints[&index].clone())
+                }
+                Token::Word(word) => {
+                    let corrected = self.correct_word(word)?;
+                    match word_list.originals.get(&index) {
+                        Some(original) => Token::Word(restore_typography(original, &corrected)),
+                        None => Token::Word(corrected),
+                    }
+                }
+                Token::Separator(_) | Token::Delimiter(_) => token.clone(),
+            });
+        }
+
+        Ok(WordList {
+            id: word_list.id.clone(),
+            id_separator: word_list.id_separator.clone(),
+            tokens,
+            leading: word_list.leading.clone(),
+            trailing: word_list.trailing.clone(),
+            line_number: word_list.line_number,
+            hints: HashMap::new(),
+            originals: HashMap::new(),
+        })
+    }
+}
@@ -0,0 +1,212 @@
+/// Per-operation costs for [`weighted_levenshtein`], measured as edits that
+/// turn the input word into a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditCosts {
+    /// Inserting a character into the input (the typist dropped a letter).
+    pub insertion: usize,
+    /// Deleting a character from the input (the typist added a stray letter).
+    pub deletion: usize,
+    /// Replacing one character with another.
+    pub substitution: usize,
+}
+
+impl Default for EditCosts {
+    fn default() -> Self {
+        EditCosts {
+            insertion: 1,
+            deletion: 1,
+            substitution: 1,
+        }
+    }
+}
+
+/// Levenshtein distance from `input` to `candidate` with configurable costs.
+/// With the default costs this is the standard Levenshtein distance.
+pub fn weighted_levenshtein(input: &str, candidate: &str, costs: &EditCosts) -> usize {
+    let input: Vec<char> = input.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+
+    let mut previous: Vec<usize> = (0..=candidate.len()).map(|j| j * costs.insertion).collect();
+    let mut current = vec![0; candidate.len() + 1];
+
+    for (i, &a) in input.iter().enumerate() {
+        current[0] = (i + 1) * costs.deletion;
+
+        for (j, &b) in candidate.iter().enumerate() {
+            let substitution = previous[j] + if a == b { 0 } else { costs.substitution };
+            let deletion = previous[j + 1] + costs.deletion;
+            let insertion = current[j] + costs.insertion;
+            current[j + 1] = substitution.min(deletion).min(insertion);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[candidate.len()]
+}
+
+/// Levenshtein distance from `input` to `candidate` if it is at most `max`,
+/// or `None` otherwise. Gives up as soon as the lengths differ by more than
+/// `max` or every entry of a row of the distance table exceeds it, since
+/// the row minimum never decreases, which bounds the cost of comparing a
+/// long junk token against long candidates.
+pub fn levenshtein_bounded(input: &str, candidate: &str, max: usize) -> Option<usize> {
+    let input: Vec<char> = input.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if input.len().abs_diff(candidate.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=candidate.len()).collect();
+    let mut current = vec![0; candidate.len() + 1];
+
+    for (i, &a) in input.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+
+        for (j, &b) in candidate.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            let deletion = previous[j + 1] + 1;
+            let insertion = current[j] + 1;
+            current[j + 1] = substitution.min(deletion).min(insertion);
+            row_min = row_min.min(current[j + 1]);
+        }
+
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Some(previous[candidate.len()]).filter(|&distance| distance <= max)
+}
+
+/// A single step of an edit script. Positions are character indices into the
+/// original input word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOp {
+    Insert {
+        position: usize,
+        ch: char,
+    },
+    Delete {
+        position: usize,
+        ch: char,
+    },
+    Substitute {
+        position: usize,
+        from: char,
+        to: char,
+    },
+    Transpose {
+        position: usize,
+        first: char,
+        second: char,
+    },
+}
+
+impl std::fmt::Display for EditOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditOp::Insert { position, ch } => {
+                write!(f, "insert '{}' at position {}", ch, position)
+            }
+            EditOp::Delete { position, ch } => {
+                write!(f, "delete '{}' at position {}", ch, position)
+            }
+            EditOp::Substitute { position, from, to } => {
+                write!(
+                    f,
+                    "replace '{}' with '{}' at position {}",
+                    from, to, position
+                )
+            }
+            EditOp::Transpose {
+                position,
+                first,
+                second,
+            } => write!(
+                f,
+                "transpose '{}' and '{}' at position {}",
+                first, second, position
+            ),
+        }
+    }
+}
+
+/// Computes a shortest edit script turning `input` into `candidate`, treating
+/// adjacent transpositions as a single edit, by backtracking through the
+/// distance table.
+pub fn edit_script(input: &str, candidate: &str) -> Vec<EditOp> {
+    let a: Vec<char> = input.chars().collect();
+    let b: Vec<char> = candidate.chars().collect();
+
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (table[i - 1][j - 1] + cost)
+                .min(table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = best;
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+
+    while i > 0 || j > 0 {
+        let current = table[i][j];
+
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && current == table[i - 1][j - 1] {
+            i -= 1;
+            j -= 1;
+        } else if i > 1
+            && j > 1
+            && a[i - 1] == b[j - 2]
+            && a[i - 2] == b[j - 1]
+            && current == table[i - 2][j - 2] + 1
+        {
+            ops.push(EditOp::Transpose {
+                position: i - 2,
+                first: a[i - 2],
+                second: a[i - 1],
+            });
+            i -= 2;
+            j -= 2;
+        } else if i > 0 && j > 0 && current == table[i - 1][j - 1] + 1 {
+            ops.push(EditOp::Substitute {
+                position: i - 1,
+                from: a[i - 1],
+                to: b[j - 1],
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && current == table[i - 1][j] + 1 {
+            ops.push(EditOp::Delete {
+                position: i - 1,
+                ch: a[i - 1],
+            });
+            i -= 1;
+        } else {
+            ops.push(EditOp::Insert {
+                position: i,
+                ch: b[j - 1],
+            });
+            j -= 1;
+        }
+    }
+
+    ops.reverse();
+    ops
+}
@@ -0,0 +1,72 @@
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use crate::error::SpellError;
+
+const BOM: char = '\u{feff}';
+
+/// Removes a leading UTF-8 byte-order mark, which would otherwise end up in
+/// the first ID or the first dictionary word.
+pub(crate) fn strip_bom(text: &str) -> &str {
+    text.strip_prefix(BOM).unwrap_or(text)
+}
+
+/// Text encodings supported for reading input and writing output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// ISO-8859-1: every byte maps to the code point of the same value.
+    Latin1,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Some(Encoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(Encoding::Latin1),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "ISO-8859-1",
+        }
+    }
+
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<String, SpellError> {
+        match self {
+            Encoding::Utf8 => String::from_utf8(bytes).map_err(|e| SpellError::Encoding {
+                encoding: self.name(),
+                reason: e.to_string(),
+            }),
+            Encoding::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+        }
+    }
+
+    /// Fails on the first character the encoding cannot represent.
+    pub fn encode(&self, text: String) -> Result<Vec<u8>, SpellError> {
+        match self {
+            Encoding::Utf8 => Ok(text.into_bytes()),
+            Encoding::Latin1 => text
+                .chars()
+                .map(|c| {
+                    u8::try_from(u32::from(c)).map_err(|_| SpellError::Encoding {
+                        encoding: self.name(),
+                        reason: format!("cannot represent '{}' (U+{:04X})", c, u32::from(c)),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> Result<String, SpellError> {
+        let bytes = fs::read(path).map_err(|e| SpellError::Io(format!("Failed to read: {}", e)))?;
+        self.decode(bytes)
+    }
+}
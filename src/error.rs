@@ -0,0 +1,40 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpellError {
+    Io(String),
+    InvalidLine {
+        line: usize,
+        reason: String,
+    },
+    LineTooLong {
+        line: usize,
+        length: usize,
+        max: usize,
+    },
+    NoEntries,
+    EmptyDictionary,
+    Encoding {
+        encoding: &'static str,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for SpellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpellError::Io(message) => write!(f, "{}", message),
+            SpellError::InvalidLine { line, reason } => write!(f, "Line {} {}", line, reason),
+            SpellError::LineTooLong { line, length, max } => write!(
+                f,
+                "Line {} is too long: {} bytes exceeds the limit of {}",
+                line, length, max
+            ),
+            SpellError::NoEntries => write!(f, "cannot find any valid entries"),
+            SpellError::EmptyDictionary => write!(f, "Dictionary is empty"),
+            SpellError::Encoding { encoding, reason } => {
+                write!(f, "{} encoding error: {}", encoding, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpellError {}
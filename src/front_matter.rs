@@ -0,0 +1,85 @@
+use crate::error::SpellError;
+
+const DELIMITER: &str = "---";
+
+/// Settings from a metadata block at the top of an input file:
+///
+/// ```text
+/// ---
+/// dictionary: other_vocabulary.txt
+/// max_distance: 2
+/// ---
+/// 0001 first entry
+/// ```
+///
+/// Each line is `key: value` (or `key = value`); blank lines and lines
+/// starting with `#` are ignored and values may be quoted. Only the first
+/// line of the file can open the block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    entries: Vec<(String, String)>,
+    /// Lines taken up by the block, delimiters included.
+    lines: usize,
+}
+
+impl FrontMatter {
+    /// Parses the block at the start of `content`, or returns `None` if the
+    /// content does not start with one.
+    pub fn parse(content: &str) -> Result<Option<FrontMatter>, SpellError> {
+        let mut lines = content.lines().enumerate();
+        match lines.next() {
+            Some((_, first)) if first.trim_end() == DELIMITER => {}
+            _ => return Ok(None),
+        }
+
+        let mut entries = Vec::new();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line == DELIMITER {
+                return Ok(Some(FrontMatter {
+                    entries,
+                    lines: i + 1,
+                }));
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once([':', '=']) else {
+                return Err(SpellError::InvalidLine {
+                    line: i + 1,
+                    reason: format!("is not a 'key: value' front matter entry: '{}'", line),
+                });
+            };
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            entries.push((key.trim().to_string(), value.to_string()));
+        }
+
+        Err(SpellError::InvalidLine {
+            line: 1,
+            reason: format!("opens a front matter block with no closing '{}'", DELIMITER),
+        })
+    }
+
+    /// Number of lines to skip before the word lists begin; 0 without a block.
+    pub fn body_start(content: &str) -> Result<usize, SpellError> {
+        Ok(Self::parse(content)?.map_or(0, |front_matter| front_matter.lines))
+    }
+
+    /// The last value given for `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
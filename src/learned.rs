@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::error::SpellError;
+use crate::output::{read_if_exists, save};
+use crate::spell_checker::SpellChecker;
+
+/// Corrections a user has confirmed, persisted between sessions in the same
+/// `from to` format as a forced-corrections file. Pass [`pairs`] to
+/// [`SpellChecker::with_learned`] so later runs apply them before searching.
+///
+/// [`pairs`]: LearnedCorrections::pairs
+#[derive(Debug, Clone)]
+pub struct LearnedCorrections {
+    path: PathBuf,
+    pairs: HashMap<String, String>,
+}
+
+impl LearnedCorrections {
+    /// Loads the corrections learned so far from `path`. A missing file is
+    /// the start of a new store, not an error.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, SpellError> {
+        let path = path.into();
+        let pairs = match read_if_exists(&path, "Failed to load learned corrections")? {
+            Some(content) => SpellChecker::parse_forced(&content)?,
+            None => HashMap::new(),
+        };
+
+        Ok(LearnedCorrections { path, pairs })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pairs(&self) -> &HashMap<String, String> {
+        &self.pairs
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Records that `original` should become `corrected`, replacing any
+    /// earlier confirmation for `original`. Returns `false` without recording
+    /// anything for unchanged words and for words containing whitespace,
+    /// which the file format cannot hold.
+    pub fn confirm(&mut self, original: &str, corrected: &str) -> bool {
+        if !learnable(original, corrected) {
+            return false;
+        }
+
+        self.pairs
+            .insert(original.to_string(), corrected.to_string());
+        true
+    }
+
+    /// Offers each distinct correction in `corrections` to `accept`, in
+    /// order, and records only those it accepts. Corrections already learned
+    /// or that [`LearnedCorrections::confirm`] would refuse are not offered.
+    /// Returns how many were recorded.
+    pub fn confirm_with<'a, I, F>(&mut self, corrections: I, mut accept: F) -> usize
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+        F: FnMut(&str, &str) -> bool,
+    {
+        let mut offered = HashSet::new();
+        let mut recorded = 0;
+
+        for (original, corrected) in corrections {
+            let known = self
+                .pairs
+                .get(original)
+                .is_some_and(|known| known == corrected);
+            if known || !learnable(original, corrected) || !offered.insert((original, corrected)) {
+                continue;
+            }
+            if accept(original, corrected) && self.confirm(original, corrected) {
+                recorded += 1;
+            }
+        }
+
+        recorded
+    }
+
+    /// Writes every learned pair back to the file, sorted so the file diffs
+    /// cleanly between sessions. The file is replaced atomically, so a failed
+    /// save keeps the pairs saved before.
+    pub fn save(&self) -> Result<(), SpellError> {
+        let mut pairs: Vec<_> = self.pairs.iter().collect();
+        pairs.sort();
+        let content: String = pairs
+            .into_iter()
+            .map(|(original, corrected)| format!("{} {}\n", original, corrected))
+            .collect();
+
+        save(
+            &self.path,
+            content.as_bytes(),
+            true,
+            "Failed to save learned corrections",
+        )
+    }
+}
+
+/// Whether the file format can hold `original -> corrected` as a change.
+fn learnable(original: &str, corrected: &str) -> bool {
+    original != corrected
+        && !original.is_empty()
+        && !corrected.is_empty()
+        && !original.contains(char::is_whitespace)
+        && !corrected.contains(char::is_whitespace)
+}
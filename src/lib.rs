@@ -0,0 +1,84 @@
+mod audit;
+mod candidates;
+mod compose;
+mod coverage;
+mod dictionary;
+#[cfg(feature = "fs")]
+mod disk_dictionary;
+mod distance;
+mod encoding;
+mod error;
+mod front_matter;
+#[cfg(feature = "fs")]
+mod learned;
+mod lint;
+mod memory;
+#[cfg(feature = "fs")]
+mod metadata;
+#[cfg(feature = "fs")]
+mod observed;
+#[cfg(feature = "fs")]
+mod output;
+#[cfg(feature = "fs")]
+mod paths;
+mod phonetic;
+#[cfg(feature = "fs")]
+mod reload;
+mod rng;
+mod sanitize;
+mod script;
+mod spell_checker;
+mod stem;
+mod stream;
+mod suggestion;
+mod summary;
+pub mod trace;
+mod typography;
+mod word_list;
+
+pub use audit::{Decision, Strategy, write_audit};
+pub use candidates::{CandidateSource, LinearScan};
+pub use coverage::CoverageReport;
+pub use dictionary::Dictionary;
+#[cfg(feature = "fs")]
+pub use disk_dictionary::DiskDictionary;
+pub use distance::{EditCosts, EditOp, edit_script, levenshtein_bounded, weighted_levenshtein};
+pub use encoding::Encoding;
+pub use error::SpellError;
+pub use front_matter::FrontMatter;
+#[cfg(feature = "fs")]
+pub use learned::LearnedCorrections;
+#[cfg(feature = "fs")]
+pub use lint::lint_file;
+pub use lint::{LintIssue, LintRule, lint_content};
+pub use memory::MemoryReport;
+#[cfg(feature = "fs")]
+pub use metadata::RunMetadata;
+#[cfg(feature = "fs")]
+pub use observed::ObservedFrequencies;
+#[cfg(feature = "fs")]
+pub use output::{
+    WordsOnly, WriteOptions, ends_with_newline, write_corrected_file, write_routed_files,
+};
+#[cfg(feature = "fs")]
+pub use paths::same_file;
+pub use phonetic::{Phonetic, double_metaphone, soundex};
+#[cfg(feature = "fs")]
+pub use reload::DictionaryWatcher;
+pub use rng::{DEFAULT_SEED, SplitMix64};
+pub use sanitize::ControlCharPolicy;
+pub use script::Script;
+pub use spell_checker::{
+    ComparisonStats, CorrectionRun, DEFAULT_MAX_CANDIDATES, Disagreement, LineDiff, LoadOptions,
+    SpellChecker, TieBreak, TokenOutcome,
+};
+pub use stem::stem;
+pub use stream::{CorrectionStats, correct_stream};
+pub use suggestion::Suggestion;
+pub use summary::{CorrectionSummary, DistanceBuckets};
+pub use typography::{TypographyPolicy, normalize_typography, restore_typography};
+pub use word_list::{ParseOptions, SeparatorMerge, SeparatorMode, Token, WordChars, WordList};
+
+/// Version of this crate, as reported by `--version` and recorded in
+/// [`RunMetadata`] headers.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,233 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use crate::encoding::strip_bom;
+use crate::error::SpellError;
+use crate::front_matter::FrontMatter;
+use crate::word_list::{ParseOptions, Token, WordList, split_line};
+
+const ID_LEN: usize = 4;
+
+/// A rule of the strict `ID words` schema checked by [`lint_content`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+    /// The ID has fewer than four characters, or none at all with
+    /// [`ParseOptions::variable_width_ids`].
+    IdLength,
+    /// A character of the ID is not an ASCII digit.
+    IdDigits,
+    /// No space or tab follows the ID.
+    MissingSeparator,
+    /// More than one space or tab follows the ID.
+    ExtraSeparator,
+    /// Nothing after the ID is a word.
+    NoWords,
+    /// A control character among the words.
+    ControlCharacter,
+    /// Longer than [`ParseOptions::max_line_length`].
+    LineTooLong,
+}
+
+impl LintRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::IdLength => "id-length",
+            LintRule::IdDigits => "id-digits",
+            LintRule::MissingSeparator => "missing-separator",
+            LintRule::ExtraSeparator => "extra-separator",
+            LintRule::NoWords => "no-words",
+            LintRule::ControlCharacter => "control-character",
+            LintRule::LineTooLong => "line-too-long",
+        }
+    }
+}
+
+/// One violation of a [`LintRule`]. Displays as a message followed by the
+/// line and a caret under the offending column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column in characters.
+    pub column: usize,
+    pub rule: LintRule,
+    pub message: String,
+    /// The offending line as written.
+    pub source: String,
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Other control characters are shown as '?' and tabs are echoed in
+        // the padding, so the caret lines up however the terminal draws them.
+        let source: String = self
+            .source
+            .chars()
+            .map(|c| if c.is_control() && c != '\t' { '?' } else { c })
+            .collect();
+        let padding: String = self
+            .source
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "Line {}, column {}: {} [{}]\n    {}\n    {}^",
+            self.line,
+            self.column,
+            self.message,
+            self.rule.name(),
+            source,
+            padding
+        )
+    }
+}
+
+/// Checks every line of `content` against the strict schema (a four-digit
+/// ASCII ID, a single space or tab, then at least one word) and collects
+/// every violation rather than stopping at the first. Blank lines and a
+/// leading front matter block are skipped; `options` decides what counts as
+/// a word and, with [`ParseOptions::variable_width_ids`], lets the ID be
+/// any number of digits.
+pub fn lint_content(content: &str, options: &ParseOptions) -> Result<Vec<LintIssue>, SpellError> {
+    let content = strip_bom(content);
+    let body_start = FrontMatter::body_start(content)?;
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .skip(body_start)
+        .flat_map(|(i, line)| lint_line(i + 1, line, options))
+        .collect())
+}
+
+/// [`lint_content`] for the file at `path`, read in the configured encoding.
+#[cfg(feature = "fs")]
+pub fn lint_file<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<Vec<LintIssue>, SpellError> {
+    lint_content(&options.encoding.read_file(path)?, options)
+}
+
+fn lint_line(line_number: usize, line: &str, options: &ParseOptions) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    // The entry as `WordList::parse_entry` sees it, without the whitespace
+    // around it; columns still count from the start of the line.
+    let Some((leading, entry, _)) = split_line(line, options) else {
+        return issues;
+    };
+    let offset = leading.chars().count();
+    let mut report = |column: usize, rule: LintRule, message: String| {
+        issues.push(LintIssue {
+            line: line_number,
+            column: offset + column,
+            rule,
+            message,
+            source: line.to_string(),
+        });
+    };
+    let chars: Vec<char> = entry.chars().collect();
+
+    let id_len = if options.variable_width_ids {
+        chars.len()
+    } else {
+        ID_LEN
+    };
+    let id_end = chars
+        .iter()
+        .take(id_len)
+        .position(|c| c.is_whitespace())
+        .unwrap_or(chars.len().min(id_len));
+    if let Some(i) = chars[..id_end].iter().position(|c| !c.is_ascii_digit()) {
+        report(
+            i + 1,
+            LintRule::IdDigits,
+            format!(
+                "ID must be ASCII digits, found '{}'",
+                chars[i].escape_debug()
+            ),
+        );
+    }
+    if options.variable_width_ids {
+        if id_end == 0 {
+            report(
+                1,
+                LintRule::IdLength,
+                "ID is empty, expected at least one digit".to_string(),
+            );
+        }
+    } else if id_end < ID_LEN {
+        report(
+            id_end + 1,
+            LintRule::IdLength,
+            format!("ID has {} character(s), expected {}", id_end, ID_LEN),
+        );
+    }
+
+    let separators = chars[id_end..]
+        .iter()
+        .take_while(|&&c| options.is_id_separator(c))
+        .count();
+    match (separators, chars.get(id_end)) {
+        (0, Some(c)) => report(
+            id_end + 1,
+            LintRule::MissingSeparator,
+            format!(
+                "expected a space after the ID, found '{}'",
+                c.escape_debug()
+            ),
+        ),
+        (0, None) => report(
+            id_end + 1,
+            LintRule::MissingSeparator,
+            "expected a space after the ID, found the end of the line".to_string(),
+        ),
+        (1, _) => {}
+        (n, _) => report(
+            id_end + 2,
+            LintRule::ExtraSeparator,
+            format!("{} spaces or tabs after the ID, expected one", n),
+        ),
+    }
+
+    let words_start = id_end + separators;
+    let words_part: String = chars[words_start..].iter().collect();
+    if !WordList::parse_tokens(&words_part, options)
+        .iter()
+        .any(|token| matches!(token, Token::Word(_)))
+    {
+        report(
+            words_start + 1,
+            LintRule::NoWords,
+            "expected at least one word after the ID".to_string(),
+        );
+    }
+
+    // Control characters before this point already break the ID.
+    for (i, c) in chars[words_start..]
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_control())
+    {
+        report(
+            words_start + i + 1,
+            LintRule::ControlCharacter,
+            format!("control character U+{:04X}", u32::from(*c)),
+        );
+    }
+
+    if let Some(max) = options.max_line_length
+        && entry.len() > max
+    {
+        let column = entry.char_indices().take_while(|&(i, _)| i < max).count() + 1;
+        report(
+            column,
+            LintRule::LineTooLong,
+            format!("{} bytes exceeds the limit of {}", entry.len(), max),
+        );
+    }
+
+    issues
+}
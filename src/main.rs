@@ -1,198 +1,890 @@
-use std::path::Path;
-use std::{fs, usize};
-use strsim::levenshtein;
-
-#[derive(Debug, Clone)]
-enum Token {
-    Word(String),
-    Separator(char),
-}
+use homework_03::{
+    ControlCharPolicy, CorrectionSummary, CoverageReport, Dictionary, DiskDictionary,
+    DistanceBuckets, EditCosts, Encoding, FrontMatter, LearnedCorrections, LoadOptions,
+    ObservedFrequencies, ParseOptions, Phonetic, RunMetadata, SeparatorMerge, SeparatorMode,
+    SpellChecker, SpellError, TieBreak, Token, TypographyPolicy, VERSION, WordChars, WordList,
+    WordsOnly, WriteOptions, ends_with_newline, lint_file, same_file, trace, write_audit,
+    write_corrected_file, write_routed_files,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-struct WordList {
-    id: String,
-    tokens: Vec<Token>,
+/// What to do about a problem in the input that does not stop the run.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Warn,
+    Error,
 }
 
-struct SpellChecker {
-    dictionary: Vec<String>,
+struct Options {
+    word_file: PathBuf,
+    dict_file: PathBuf,
+    output_file: PathBuf,
+    /// Whether `output_file` was given with `--output` rather than defaulted.
+    explicit_output: bool,
+    frequency_file: Option<PathBuf>,
+    penalty_base: Option<f64>,
+    /// `None` uses the dictionary's suggested threshold; `Some(None)` disables it.
+    max_distance: Option<Option<usize>>,
+    /// Length-scaled threshold; replaces the suggested default when set.
+    max_distance_ratio: Option<f64>,
+    drop_unknown: bool,
+    max_corrections_per_line: Option<usize>,
+    /// Lines with at least this share of known words are not corrected.
+    skip_if_clean: Option<f64>,
+    max_segments: Option<usize>,
+    self_test: bool,
+    /// Turn likely mistakes, such as using the input as its own dictionary,
+    /// into errors instead of warnings.
+    strict: bool,
+    /// Print the version and exit.
+    version: bool,
+    edit_costs: Option<EditCosts>,
+    stats: bool,
+    trace: bool,
+    same_script: bool,
+    exhaustive: bool,
+    tie_break: Option<TieBreak>,
+    annotate_distance: bool,
+    /// Dictionaries with fewer entries than this trigger a warning.
+    min_dictionary_size: usize,
+    /// Only check membership when the dictionary is below `min_dictionary_size`.
+    check_only_if_small: bool,
+    /// Worker threads for correction. `None` uses one per logical CPU.
+    jobs: Option<usize>,
+    /// Time this many correction runs instead of writing output.
+    benchmark: Option<usize>,
+    validate_only: bool,
+    /// Report every schema violation with its column instead of correcting.
+    lint: bool,
+    /// Print corrections for this many lines instead of writing output.
+    preview: Option<usize>,
+    /// Pick the preview lines at random (from `seed`) rather than the first ones.
+    preview_sample: bool,
+    /// Report entries sharing an ID.
+    check_duplicate_ids: Option<Severity>,
+    /// Look words up in the sorted dictionary file instead of loading it.
+    disk_dictionary: bool,
+    /// Report dictionary coverage of the input instead of correcting it.
+    coverage: bool,
+    /// Correct the input as plain text instead of `ID words` lines.
+    freeform: bool,
+    /// Let the known share of a line widen or narrow its distance limit.
+    context_distance: bool,
+    /// Compare every correction against a whole-dictionary scan instead of
+    /// correcting.
+    cross_check: bool,
+    stemming: bool,
+    phonetic: Option<Phonetic>,
+    seed: Option<u64>,
+    forbid_file: Option<PathBuf>,
+    corrections_file: Option<PathBuf>,
+    /// Words to leave unchanged without suggesting them, one per line.
+    accept_file: Option<PathBuf>,
+    /// Confirmed corrections from earlier runs, applied like forced ones.
+    learned_file: Option<PathBuf>,
+    /// Ask which of this run's corrections to add to `learned_file`.
+    learn: bool,
+    /// Word counts from earlier output, read as extra frequencies and
+    /// updated with this run's output.
+    observed_file: Option<PathBuf>,
+    /// Append one JSON line per corrected word token to this file.
+    audit_log: Option<PathBuf>,
+    output_encoding: Encoding,
+    load: LoadOptions,
+    /// Print the N most frequent corrections after the run.
+    summary: Option<usize>,
+    /// Report corrections bucketed by edit distance.
+    bucket_by_distance: bool,
+    /// Also write each bucket's corrections to its own file.
+    bucket_files: bool,
+    /// `(ID prefix, path)` rules; lines matching none go to `output_file`.
+    routes: Vec<(String, PathBuf)>,
+    ignore_case: bool,
+    normalize_case: bool,
+    /// Correct numeric tokens instead of leaving them unchanged.
+    correct_numeric: bool,
+    skip_acronyms: bool,
+    collapse_repeats: bool,
+    max_symbol_ratio: Option<f64>,
+    max_candidates: Option<usize>,
+    cache: bool,
+    /// `None` mirrors whether the input file ends with a newline.
+    trailing_newline: Option<bool>,
+    merge_separators: Option<SeparatorMerge>,
+    trim_trailing_separators: bool,
+    words_only: Option<WordsOnly>,
+    /// Write the corrections back over the input file.
+    in_place: bool,
+    /// Remove words repeating the previous one, e.g. "the the".
+    dedup_words: bool,
+    /// Prepend a comment block recording how the output was produced.
+    emit_metadata: bool,
+    /// Only lines in this 1-based range are corrected; the rest pass through.
+    from_line: Option<usize>,
+    to_line: Option<usize>,
+    parse: ParseOptions,
 }
 
-impl WordList {
-    fn read_and_get(path: &str) -> Result<Vec<WordList>, String> {
-        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
-        Self::parse_content(&content)
-    }
-
-    fn parse_content(content: &str) -> Result<Vec<WordList>, String> {
-        content
-            .lines()
-            .enumerate()
-            .map(|(i, line)| (i + 1, line.trim()))
-            .filter(|(_, line)| !line.is_empty())
-            .map(|(line_num, line)| Self::parse_line(line_num, line))
-            .collect::<Result<Vec<WordList>, String>>()
-            .and_then(|entries| {
-                if entries.is_empty() {
-                    Err("cannot find any valid entries".to_string())
-                } else {
-                    Ok(entries)
-                }
-            })
-    }
-
-    fn parse_line(line_number: usize, line: &str) -> Result<WordList, String> {
-        if line.len() < 5 {
-            return Err(format!("Line {} is too short: '{}'", line_number, line));
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            word_file: Path::new("problem").join("words.txt"),
+            dict_file: Path::new("problem").join("vocabulary.txt"),
+            output_file: Path::new("problem").join("correction_words.txt"),
+            explicit_output: false,
+            frequency_file: None,
+            penalty_base: None,
+            max_distance: None,
+            max_distance_ratio: None,
+            drop_unknown: false,
+            max_corrections_per_line: None,
+            skip_if_clean: None,
+            max_segments: None,
+            self_test: false,
+            strict: false,
+            version: false,
+            edit_costs: None,
+            stats: false,
+            trace: false,
+            same_script: false,
+            exhaustive: false,
+            tie_break: None,
+            annotate_distance: false,
+            min_dictionary_size: 10,
+            check_only_if_small: false,
+            jobs: None,
+            benchmark: None,
+            validate_only: false,
+            lint: false,
+            preview: None,
+            preview_sample: false,
+            check_duplicate_ids: None,
+            disk_dictionary: false,
+            coverage: false,
+            freeform: false,
+            context_distance: false,
+            cross_check: false,
+            stemming: false,
+            phonetic: None,
+            seed: None,
+            forbid_file: None,
+            corrections_file: None,
+            accept_file: None,
+            learned_file: None,
+            audit_log: None,
+            observed_file: None,
+            learn: false,
+            output_encoding: Encoding::Utf8,
+            load: LoadOptions::default(),
+            summary: None,
+            bucket_by_distance: false,
+            bucket_files: false,
+            routes: Vec::new(),
+            ignore_case: false,
+            normalize_case: false,
+            correct_numeric: false,
+            skip_acronyms: false,
+            collapse_repeats: false,
+            max_symbol_ratio: None,
+            max_candidates: None,
+            cache: false,
+            trailing_newline: None,
+            merge_separators: None,
+            trim_trailing_separators: false,
+            words_only: None,
+            in_place: false,
+            dedup_words: false,
+            emit_metadata: false,
+            from_line: None,
+            to_line: None,
+            parse: ParseOptions::default(),
         }
+    }
+}
 
-        let id = line[0..4].to_string();
+impl Options {
+    fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut options = Options::default();
 
-        if !id.chars().all(|c| c.is_ascii_digit()) {
-            return Err(format!("Line {} has invalid ID: '{}'", line_number, id));
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--max-line-length" => {
+                    options.parse.max_line_length = Some(Self::parse_value(&mut args, &arg)?);
+                }
+                "--lenient" => options.parse.lenient = true,
+                "--collapse-id-separator" => options.parse.collapse_id_separator = true,
+                "--variable-width-ids" => options.parse.variable_width_ids = true,
+                "--word-chars" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.parse.word_chars = match value.as_str() {
+                        "any" => WordChars::Any,
+                        "letters" => WordChars::Letters,
+                        "alphanumeric" => WordChars::Alphanumeric,
+                        "alphanumeric-apostrophe" => WordChars::AlphanumericApostrophe,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected any, letters, alphanumeric or alphanumeric-apostrophe)",
+                                arg, value
+                            ));
+                        }
+                    };
+                }
+                "--preserve-tags" => options.parse.preserve_tags = true,
+                "--normalize-typography" => {
+                    if options.parse.typography == TypographyPolicy::Keep {
+                        options.parse.typography = TypographyPolicy::Normalize;
+                    }
+                }
+                "--restore-typography" => options.parse.typography = TypographyPolicy::Restore,
+                "--parse-hints" => {
+                    if options.parse.hint_markers.is_none() {
+                        options.parse.hint_markers = Some(("{->".to_string(), "}".to_string()));
+                    }
+                }
+                "--hint-syntax" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    let Some((open, close)) = value.split_once("...") else {
+                        return Err(format!(
+                            "Invalid value for {}: '{}' (expected the markup around '...', e.g. '{{->...}}')",
+                            arg, value
+                        ));
+                    };
+                    if open.is_empty() || close.is_empty() {
+                        return Err(format!(
+                            "Invalid value for {}: '{}' (both markers must be non-empty)",
+                            arg, value
+                        ));
+                    }
+                    options.parse.hint_markers = Some((open.to_string(), close.to_string()));
+                }
+                "--delimiter" => {
+                    let delimiter = Self::next_value(&mut args, &arg)?;
+                    options.parse.delimiters.push(delimiter);
+                }
+                "--split-on" => {
+                    let separator = Self::parse_value(&mut args, &arg)?;
+                    options.parse.set_separator(separator, SeparatorMode::Split);
+                }
+                "--keep-in-word" => {
+                    let separator = Self::parse_value(&mut args, &arg)?;
+                    options.parse.set_separator(separator, SeparatorMode::Keep);
+                }
+                "--frequencies" => {
+                    options.frequency_file = Some(Self::next_value(&mut args, &arg)?.into())
+                }
+                "--penalty-base" => {
+                    let base: f64 = Self::parse_value(&mut args, &arg)?;
+                    if !(base > 0.0 && base < 1.0) {
+                        return Err(format!("{} must be between 0 and 1, got {}", arg, base));
+                    }
+                    options.penalty_base = Some(base);
+                }
+                "--max-distance" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.max_distance = Some(if value == "unlimited" {
+                        None
+                    } else {
+                        Some(
+                            value
+                                .parse()
+                                .map_err(|_| format!("Invalid value for {}: '{}'", arg, value))?,
+                        )
+                    });
+                }
+                "--max-distance-ratio" => {
+                    let ratio: f64 = Self::parse_value(&mut args, &arg)?;
+                    if !(ratio >= 0.0 && ratio.is_finite()) {
+                        return Err(format!(
+                            "Invalid value for {}: '{}' (expected a non-negative number)",
+                            arg, ratio
+                        ));
+                    }
+                    options.max_distance_ratio = Some(ratio);
+                }
+                "--drop-unknown" => options.drop_unknown = true,
+                "--skip-if-clean" => {
+                    options.skip_if_clean = Some(Self::parse_fraction(&mut args, &arg)?);
+                }
+                "--max-corrections-per-line" => {
+                    options.max_corrections_per_line = Some(Self::parse_value(&mut args, &arg)?);
+                }
+                "--edit-costs" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.edit_costs = Some(Self::parse_edit_costs(&value).ok_or_else(|| {
+                        format!(
+                            "Invalid value for {}: '{}' (expected INSERT,DELETE,SUBSTITUTE)",
+                            arg, value
+                        )
+                    })?);
+                }
+                "--trailing-newline" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.trailing_newline = match value.as_str() {
+                        "always" => Some(true),
+                        "never" => Some(false),
+                        "match" => None,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected always, never or match)",
+                                arg, value
+                            ));
+                        }
+                    };
+                }
+                "--merge-separators" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.merge_separators = Some(match value.as_str() {
+                        "first" => SeparatorMerge::First,
+                        "last" => SeparatorMerge::Last,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected first or last)",
+                                arg, value
+                            ));
+                        }
+                    });
+                }
+                "--check-duplicate-ids" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.check_duplicate_ids = Some(match value.as_str() {
+                        "warn" => Severity::Warn,
+                        "error" => Severity::Error,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected warn or error)",
+                                arg, value
+                            ));
+                        }
+                    });
+                }
+                "--preview" => options.preview = Some(Self::parse_value(&mut args, &arg)?),
+                "--preview-sample" => options.preview_sample = true,
+                "--tie-break" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.tie_break = Some(match value.as_str() {
+                        "alphabetical" => TieBreak::Alphabetical,
+                        "frequency" => TieBreak::Frequency,
+                        "shortest" => TieBreak::ShortestFirst,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected alphabetical, frequency or shortest)",
+                                arg, value
+                            ));
+                        }
+                    });
+                }
+                "--same-script" => options.same_script = true,
+                "--exhaustive" => options.exhaustive = true,
+                "--cross-check" => options.cross_check = true,
+                "--annotate-distance" => options.annotate_distance = true,
+                "--min-dictionary-size" => {
+                    options.min_dictionary_size = Self::parse_value(&mut args, &arg)?;
+                }
+                "--check-only-if-small" => options.check_only_if_small = true,
+                "--jobs" => {
+                    let jobs: usize = Self::parse_value(&mut args, &arg)?;
+                    if jobs == 0 {
+                        return Err(format!("Invalid value for {}: must be at least 1", arg));
+                    }
+                    options.jobs = Some(jobs);
+                }
+                "--validate-only" => options.validate_only = true,
+                "--lint" => options.lint = true,
+                "--coverage" => options.coverage = true,
+                "--benchmark" => options.benchmark = Some(Self::parse_value(&mut args, &arg)?),
+                "--freeform" => options.freeform = true,
+                "--context-distance" => options.context_distance = true,
+                "--disk-dictionary" => options.disk_dictionary = true,
+                "--stem" => options.stemming = true,
+                "--phonetic" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    options.phonetic = Some(Phonetic::from_name(&value).ok_or_else(|| {
+                        format!(
+                            "Invalid value for {}: '{}' (expected soundex or metaphone)",
+                            arg, value
+                        )
+                    })?);
+                }
+                "--seed" => options.seed = Some(Self::parse_value(&mut args, &arg)?),
+                "--forbid-file" => {
+                    options.forbid_file = Some(Self::next_value(&mut args, &arg)?.into())
+                }
+                "--corrections-file" => {
+                    options.corrections_file = Some(Self::next_value(&mut args, &arg)?.into())
+                }
+                "--accept-file" => {
+                    options.accept_file = Some(Self::next_value(&mut args, &arg)?.into())
+                }
+                "--input-encoding" => {
+                    options.parse.encoding = Self::parse_encoding(&mut args, &arg)?
+                }
+                "--output-encoding" => {
+                    options.output_encoding = Self::parse_encoding(&mut args, &arg)?
+                }
+                "--control-chars" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    let policy = match value.as_str() {
+                        "strip" => ControlCharPolicy::Strip,
+                        "reject" => ControlCharPolicy::Reject,
+                        "keep" => ControlCharPolicy::Keep,
+                        _ => {
+                            return Err(format!(
+                                "Invalid value for {}: '{}' (expected strip, reject or keep)",
+                                arg, value
+                            ));
+                        }
+                    };
+                    options.parse.control_chars = policy;
+                    options.load.control_chars = policy;
+                }
+                "--summary" => options.summary = Some(20),
+                "--bucket-by" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    if value != "distance" {
+                        return Err(format!(
+                            "Invalid value for {}: '{}' (expected distance)",
+                            arg, value
+                        ));
+                    }
+                    options.bucket_by_distance = true;
+                }
+                "--bucket-files" => {
+                    options.bucket_by_distance = true;
+                    options.bucket_files = true;
+                }
+                "--summary-size" => options.summary = Some(Self::parse_value(&mut args, &arg)?),
+                "--ignore-case" => options.ignore_case = true,
+                "--normalize-case" => options.normalize_case = true,
+                "--correct-numeric" => options.correct_numeric = true,
+                "--skip-acronyms" => options.skip_acronyms = true,
+                "--collapse-repeats" => options.collapse_repeats = true,
+                "--max-symbol-ratio" => {
+                    options.max_symbol_ratio = Some(Self::parse_fraction(&mut args, &arg)?);
+                }
+                "--max-candidates" => {
+                    options.max_candidates = Some(Self::parse_value(&mut args, &arg)?);
+                }
+                "--from" => options.from_line = Some(Self::parse_value(&mut args, &arg)?),
+                "--to" => options.to_line = Some(Self::parse_value(&mut args, &arg)?),
+                "--cache" => options.cache = true,
+                "--emit-metadata" => options.emit_metadata = true,
+                "--dedup-words" => options.dedup_words = true,
+                "--trim-trailing-separator" => options.trim_trailing_separators = true,
+                "--words-only" => options.words_only = Some(WordsOnly::Joined),
+                "--word-per-line" => options.words_only = Some(WordsOnly::PerLine),
+                "--in-place" => options.in_place = true,
+                "--output" => {
+                    options.output_file = Self::next_value(&mut args, &arg)?.into();
+                    options.explicit_output = true;
+                }
+                "--drop-unmatchable" => options.load.drop_unmatchable = true,
+                "--dictionary-retries" => {
+                    options.load.retries = Self::parse_value(&mut args, &arg)?;
+                }
+                "--learned" => {
+                    options.learned_file = Some(PathBuf::from(Self::next_value(&mut args, &arg)?));
+                }
+                "--learn" => options.learn = true,
+                "--observe-frequencies" => {
+                    options.observed_file = Some(PathBuf::from(Self::next_value(&mut args, &arg)?));
+                }
+                "--audit-log" => {
+                    options.audit_log = Some(PathBuf::from(Self::next_value(&mut args, &arg)?));
+                }
+                "--route" => {
+                    let value = Self::next_value(&mut args, &arg)?;
+                    let Some((prefix, path)) = value.split_once('=') else {
+                        return Err(format!(
+                            "Invalid value for {}: '{}' (expected PREFIX=PATH)",
+                            arg, value
+                        ));
+                    };
+                    options
+                        .routes
+                        .push((prefix.to_string(), PathBuf::from(path)));
+                }
+                "--trace" => options.trace = true,
+                "--stats" => options.stats = true,
+                "--self-test" => options.self_test = true,
+                "--strict" => options.strict = true,
+                "--version" => options.version = true,
+                "--segment" => options.max_segments = Some(Self::parse_value(&mut args, &arg)?),
+                _ => return Err(format!("Unknown argument: '{}'", arg)),
+            }
         }
 
-        let words_part = &line[5..];
-        let tokens = Self::parse_tokens(words_part);
-
-        if !tokens.iter().any(|token| matches!(token, Token::Word(_))) {
+        if options.from_line == Some(0) || options.to_line == Some(0) {
+            return Err("Line numbers for --from and --to start at 1".to_string());
+        }
+        if let (Some(from), Some(to)) = (options.from_line, options.to_line)
+            && from > to
+        {
             return Err(format!(
-                "Line {} has no valid words: '{}'",
-                line_number, line
+                "Invalid line range: --from {} is after --to {}",
+                from, to
             ));
         }
+        if options.benchmark == Some(0) {
+            return Err("--benchmark needs at least one run".to_string());
+        }
+        if options.learn && options.learned_file.is_none() {
+            return Err("--learn needs a file to learn into, given with --learned".to_string());
+        }
+        if options.in_place {
+            if !options.routes.is_empty() {
+                return Err(
+                    "--in-place cannot be combined with --route, which writes other files"
+                        .to_string(),
+                );
+            }
+            if options.words_only.is_some() {
+                return Err(
+                    "--in-place cannot be combined with --words-only or --word-per-line, \
+                     whose output cannot be read back as input"
+                        .to_string(),
+                );
+            }
+            if options.explicit_output {
+                return Err(
+                    "--in-place cannot be combined with --output, which names another file"
+                        .to_string(),
+                );
+            }
+            if options.emit_metadata {
+                return Err(
+                    "--in-place cannot be combined with --emit-metadata, which would add \
+                     another header to the input on every run"
+                        .to_string(),
+                );
+            }
+            if options.annotate_distance {
+                return Err(
+                    "--in-place cannot be combined with --annotate-distance, whose \
+                     markers would be written into the input"
+                        .to_string(),
+                );
+            }
+            options.output_file = options.word_file.clone();
+        }
+        options.load.separators = options.parse.split_separators();
+
+        Ok(options)
+    }
 
-        Ok(WordList { id, tokens })
+    fn in_line_range(&self, line_number: usize) -> bool {
+        self.from_line.is_none_or(|from| line_number >= from)
+            && self.to_line.is_none_or(|to| line_number <= to)
     }
 
-    fn parse_tokens(words_part: &str) -> Vec<Token> {
-        let mut tokens = Vec::new();
-        let mut current_word = String::new();
+    /// Applies settings from the word file's front matter, if it has any.
+    /// A relative dictionary path is resolved against the word file's directory.
+    /// Fills in settings from the input's front matter. Settings given on
+    /// the command line win.
+    fn apply_front_matter(&mut self) -> Result<(), String> {
+        let content = self
+            .parse
+            .encoding
+            .read_file(&self.word_file)
+            .map_err(|e| e.to_string())?;
+        let Some(front_matter) = FrontMatter::parse(&content).map_err(|e| e.to_string())? else {
+            return Ok(());
+        };
 
-        for char in words_part.chars() {
-            if char == ' ' || char == '/' {
-                if !current_word.is_empty() {
-                    tokens.push(Token::Word(current_word));
-                    current_word = String::new();
+        for (key, value) in front_matter.entries() {
+            match key {
+                "dictionary" => {
+                    let base = self.word_file.parent().unwrap_or(Path::new(""));
+                    self.dict_file = base.join(value);
                 }
-                tokens.push(Token::Separator(char));
-            } else {
-                current_word.push(char);
+                "max_distance" if self.max_distance.is_some() => {}
+                "max_distance" => {
+                    self.max_distance =
+                        Some(if value == "unlimited" {
+                            None
+                        } else {
+                            Some(value.parse().map_err(|_| {
+                                format!("Invalid value for max_distance: '{}'", value)
+                            })?)
+                        });
+                }
+                _ => println!("Warning: ignoring unknown front matter key '{}'", key),
             }
         }
 
-        if !current_word.is_empty() {
-            tokens.push(Token::Word(current_word));
-        }
-
-        tokens
+        Ok(())
     }
-}
 
-impl SpellChecker {
-    fn new(dict_path: &str) -> Result<Self, String> {
-        let dict_content = fs::read_to_string(dict_path)
-            .map_err(|e| format!("Failed to load dictionary: {}", e))?;
+    /// Output settings, resolving the trailing newline against the input
+    /// file when it was not set explicitly.
+    fn write_options(&self) -> Result<WriteOptions, SpellError> {
+        let trailing_newline = match self.trailing_newline {
+            Some(trailing_newline) => trailing_newline,
+            None => ends_with_newline(&self.word_file)?,
+        };
 
-        let mut dictionary: Vec<String> = dict_content
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+        let header = if self.emit_metadata {
+            Some(RunMetadata::collect(&self.dict_file, std::env::args().skip(1))?.to_string())
+        } else {
+            None
+        };
 
-        if dictionary.is_empty() {
-            return Err("Dictionary is empty".to_string());
-        }
+        // Lines that are not entries are copied from the input, which a
+        // freeform document has none of.
+        let source = if self.in_place && !self.freeform {
+            Some(self.parse.encoding.read_file(&self.word_file)?)
+        } else {
+            None
+        };
 
-        dictionary.sort_unstable();
+        Ok(WriteOptions {
+            trailing_newline,
+            encoding: self.output_encoding,
+            merge_separators: self.merge_separators,
+            trim_trailing_separators: self.trim_trailing_separators,
+            header,
+            words_only: self.words_only,
+            atomic: self.in_place,
+            source,
+        })
+    }
 
-        Ok(SpellChecker { dictionary })
+    fn next_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+        args.next()
+            .ok_or_else(|| format!("Missing value for {}", flag))
     }
 
-    fn contains_word(&self, word: &str) -> bool {
-        self.dictionary.binary_search(&word.to_string()).is_ok()
+    fn parse_encoding<I: Iterator<Item = String>>(
+        args: &mut I,
+        flag: &str,
+    ) -> Result<Encoding, String> {
+        let value = Self::next_value(args, flag)?;
+        Encoding::from_name(&value).ok_or_else(|| {
+            format!(
+                "Invalid value for {}: '{}' (expected utf-8 or latin-1)",
+                flag, value
+            )
+        })
     }
 
-    fn correct_word(&self, word: &str) -> String {
-        if self.contains_word(word) {
-            return word.to_string();
+    fn parse_edit_costs(value: &str) -> Option<EditCosts> {
+        let costs: Vec<usize> = value
+            .split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect::<Option<_>>()?;
+
+        match costs[..] {
+            [insertion, deletion, substitution] => Some(EditCosts {
+                insertion,
+                deletion,
+                substitution,
+            }),
+            _ => None,
         }
+    }
 
-        let mut best_match = word.to_string();
-        let mut min_distance = usize::MAX;
+    fn parse_value<T: FromStr, I: Iterator<Item = String>>(
+        args: &mut I,
+        flag: &str,
+    ) -> Result<T, String> {
+        let value = Self::next_value(args, flag)?;
+        value
+            .parse::<T>()
+            .map_err(|_| format!("Invalid value for {}: '{}'", flag, value))
+    }
 
-        for correct_word in &self.dictionary {
-            let distance = levenshtein(word, correct_word);
+    /// A share between 0 and 1 inclusive.
+    fn parse_fraction<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<f64, String> {
+        let fraction: f64 = Self::parse_value(args, flag)?;
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(format!(
+                "Invalid value for {}: '{}' (expected a number from 0 to 1)",
+                flag, fraction
+            ));
+        }
+        Ok(fraction)
+    }
+}
 
-            if distance < min_distance {
-                min_distance = distance;
-                best_match = correct_word.clone();
+/// Asks on stdin whether to learn `original -> corrected`. Anything but "y"
+/// or "yes", including the end of input, declines.
+fn ask_to_learn(original: &str, corrected: &str) -> bool {
+    print!("Learn '{}' -> '{}'? [y/N] ", original, corrected);
+    let _ = io::stdout().flush();
 
-                if distance <= 1 {
-                    break;
-                }
-            }
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Reads the input entries, reporting lines skipped in lenient mode on
+/// stderr so they never mix with corrected output.
+fn read_word_lists(path: &Path, options: &ParseOptions) -> Result<Vec<WordList>, SpellError> {
+    let (word_lists, skipped) = WordList::read_with_skipped(path, options)?;
+    for e in &skipped {
+        eprintln!("Warning: skipping {}", e);
+    }
+    Ok(word_lists)
+}
+
+fn main() {
+    let mut options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
         }
+    };
 
-        best_match
+    if options.version {
+        println!("homework_03 {}", VERSION);
+        return;
     }
 
-    fn correct_word_list(&self, word_list: &WordList) -> WordList {
-        let corrected_tokens: Vec<Token> = word_list
-            .tokens
-            .iter()
-            .map(|token| match token {
-                Token::Word(word) => Token::Word(self.correct_word(word)),
-                Token::Separator(c) => Token::Separator(*c),
-            })
-            .collect();
+    trace::init_from_env();
+    if options.trace {
+        trace::set_enabled(true);
+    }
 
-        WordList {
-            id: word_list.id.clone(),
-            tokens: corrected_tokens,
-        }
+    if !options.self_test && !options.word_file.exists() {
+        println!(
+            "Error: File '{}' does not exist",
+            options.word_file.display()
+        );
+        return;
     }
-}
 
-impl std::fmt::Display for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Token::Word(word) => write!(f, "{}", word),
-            Token::Separator(c) => write!(f, "{}", c),
-        }
+    if !options.self_test
+        && let Err(e) = options.apply_front_matter()
+    {
+        println!("Failed to read front matter: {}", e);
+        return;
     }
-}
 
-impl std::fmt::Display for WordList {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ", self.id)?;
-        for token in &self.tokens {
-            write!(f, "{}", token)?;
+    let word_file = options.word_file.as_path();
+    let dict_file = options.dict_file.as_path();
+    let output_file = options.output_file.as_path();
+
+    if options.lint {
+        match lint_file(word_file, &options.parse) {
+            Ok(issues) if issues.is_empty() => {
+                println!("{} has no lint problems", word_file.display())
+            }
+            Ok(issues) => {
+                for issue in &issues {
+                    println!("{}", issue);
+                }
+                println!(
+                    "{} has {} lint problem(s)",
+                    word_file.display(),
+                    issues.len()
+                );
+            }
+            Err(e) => println!("Failed to read word file: {}", e),
         }
-        Ok(())
+        return;
     }
-}
 
-fn main() {
-    let word_file = "problem/words.txt";
-    let dict_file = "problem/vocabulary.txt";
-    let output_file = "problem/correction_words.txt";
+    if options.validate_only {
+        match WordList::validate_file(word_file, &options.parse) {
+            Ok(()) => println!("{} is valid", word_file.display()),
+            Err(errors) => {
+                for e in &errors {
+                    println!("{}", e);
+                }
+                println!("{} has {} problem(s)", word_file.display(), errors.len());
+            }
+        }
+        return;
+    }
 
-    if !Path::new(word_file).exists() {
-        println!("Error: File '{}' does not exist", word_file);
+    if !dict_file.exists() {
+        println!(
+            "Error: Dictionary file '{}' does not exist",
+            dict_file.display()
+        );
         return;
     }
 
-    if !Path::new(dict_file).exists() {
-        println!("Error: Dictionary file '{}' does not exist", dict_file);
+    if same_file(dict_file, word_file) {
+        let message = format!(
+            "dictionary and input are the same file '{}'",
+            dict_file.display()
+        );
+        if options.strict {
+            println!("Error: {}", message);
+            return;
+        }
+        println!("Warning: {}", message);
+    }
+
+    if options.disk_dictionary {
+        // Only plain nearest-match correction is available from disk; the
+        // in-memory options (frequencies, segmentation, ...) do not apply.
+        let result = DiskDictionary::open(dict_file).and_then(|dictionary| {
+            let dictionary = dictionary.with_ignore_numeric(!options.correct_numeric);
+            let mut dictionary = match options.max_distance.flatten() {
+                Some(max_distance) => dictionary.with_max_distance(max_distance),
+                None => dictionary,
+            };
+            let corrected = read_word_lists(word_file, &options.parse)?
+                .iter()
+                .map(|word_list| dictionary.correct_word_list(word_list))
+                .collect::<Result<Vec<_>, _>>()?;
+            write_corrected_file(&corrected, output_file, &options.write_options()?)
+        });
+
+        match result {
+            Ok(_) => println!(
+                "Correction completed! Result saved to {}",
+                output_file.display()
+            ),
+            Err(e) => println!("Failed to correct with on-disk dictionary: {}", e),
+        }
         return;
     }
 
-    let spell_checker = match SpellChecker::new(dict_file) {
+    let mut spell_checker = match SpellChecker::load(dict_file, &options.load) {
         Ok(checker) => {
             println!(
                 "Dictionary loaded successfully with {} words",
-                checker.dictionary.len()
+                checker.len()
             );
+            if checker.sanitized_entries() > 0 {
+                println!(
+                    "Warning: {} dictionary entries contained control characters",
+                    checker.sanitized_entries()
+                );
+            }
+            if checker.unmatchable_entries() > 0 {
+                println!(
+                    "Warning: {} dictionary entries contain a separator and can never match{}",
+                    checker.unmatchable_entries(),
+                    if options.load.drop_unmatchable {
+                        "; dropped"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            if checker.len() < options.min_dictionary_size {
+                println!(
+                    "Warning: dictionary has only {} words (fewer than {}); {}",
+                    checker.len(),
+                    options.min_dictionary_size,
+                    if options.check_only_if_small {
+                        "checking membership only"
+                    } else {
+                        "corrections are likely to be nonsensical"
+                    }
+                );
+            }
             checker
         }
         Err(e) => {
@@ -201,12 +893,213 @@ fn main() {
         }
     };
 
-    let word_lists = match WordList::read_and_get(word_file) {
+    let mut frequencies = HashMap::new();
+    if let Some(frequency_file) = &options.frequency_file {
+        match SpellChecker::load_frequencies(frequency_file) {
+            Ok(loaded) => {
+                println!("Loaded {} word frequencies", loaded.len());
+                frequencies = loaded;
+            }
+            Err(e) => {
+                println!("Failed to load frequency list: {}", e);
+                return;
+            }
+        }
+    }
+
+    let mut observed = match &options.observed_file {
+        Some(observed_file) => match ObservedFrequencies::open(observed_file) {
+            Ok(observed) => {
+                println!("Loaded {} observed word frequencies", observed.len());
+                for (word, count) in observed.counts() {
+                    *frequencies.entry(word.clone()).or_insert(0) += count;
+                }
+                Some(observed)
+            }
+            Err(e) => {
+                println!("Failed to load observed frequencies: {}", e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    if !frequencies.is_empty() {
+        spell_checker = spell_checker.with_frequencies(frequencies);
+    }
+
+    if let Some(forbid_file) = &options.forbid_file {
+        match SpellChecker::load_forbidden(forbid_file) {
+            Ok(forbidden) => {
+                println!("Loaded {} forbidden substitutions", forbidden.len());
+                spell_checker = spell_checker.with_forbidden(forbidden);
+            }
+            Err(e) => {
+                println!("Failed to load forbidden substitutions: {}", e);
+                return;
+            }
+        }
+    }
+
+    if let Some(corrections_file) = &options.corrections_file {
+        match SpellChecker::load_forced(corrections_file) {
+            Ok(forced) => {
+                println!("Loaded {} forced corrections", forced.len());
+                spell_checker = spell_checker.with_forced(forced);
+            }
+            Err(e) => {
+                println!("Failed to load forced corrections: {}", e);
+                return;
+            }
+        }
+    }
+
+    if let Some(accept_file) = &options.accept_file {
+        match Dictionary::load(accept_file, &options.load) {
+            Ok(accepted) => {
+                println!("Loaded {} accepted words", accepted.len());
+                spell_checker = spell_checker.with_accepted(accepted);
+            }
+            Err(e) => {
+                println!("Failed to load accepted words: {}", e);
+                return;
+            }
+        }
+    }
+
+    let mut learned = match &options.learned_file {
+        Some(learned_file) => match LearnedCorrections::open(learned_file) {
+            Ok(learned) => {
+                println!("Loaded {} learned corrections", learned.len());
+                spell_checker = spell_checker.with_learned(learned.pairs().clone());
+                Some(learned)
+            }
+            Err(e) => {
+                println!("Failed to load learned corrections: {}", e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    if let Some(penalty_base) = options.penalty_base {
+        spell_checker = spell_checker.with_penalty_base(penalty_base);
+    }
+
+    let max_distance = options.max_distance.unwrap_or_else(|| {
+        options
+            .max_distance_ratio
+            .is_none()
+            .then(|| spell_checker.suggested_max_distance())
+    });
+    if let Some(max_distance) = max_distance {
+        spell_checker = spell_checker.with_max_distance(max_distance);
+    }
+    if let Some(limit) = options.max_corrections_per_line {
+        spell_checker = spell_checker.with_max_corrections_per_line(limit);
+    }
+    if let Some(ratio) = options.skip_if_clean {
+        spell_checker = spell_checker.with_skip_if_clean(ratio);
+    }
+    if let Some(ratio) = options.max_distance_ratio {
+        spell_checker = spell_checker.with_max_distance_ratio(ratio);
+    }
+
+    let small = spell_checker.len() < options.min_dictionary_size;
+    spell_checker = spell_checker
+        .with_drop_unknown(options.drop_unknown)
+        .with_same_script(options.same_script)
+        .with_exhaustive(options.exhaustive)
+        .with_annotate_distance(options.annotate_distance)
+        .with_check_only(options.check_only_if_small && small)
+        .with_context_distance(options.context_distance)
+        .with_stemming(options.stemming)
+        .with_phonetic(options.phonetic)
+        .with_ignore_case(options.ignore_case)
+        .with_normalize_case(options.normalize_case)
+        .with_ignore_numeric(!options.correct_numeric)
+        .with_skip_acronyms(options.skip_acronyms)
+        .with_collapse_repeats(options.collapse_repeats)
+        .with_cache(options.cache);
+
+    if let Some(ratio) = options.max_symbol_ratio {
+        spell_checker = spell_checker.with_max_symbol_ratio(ratio);
+    }
+    if let Some(max) = options.max_candidates {
+        spell_checker = spell_checker.with_max_candidates(max);
+    }
+
+    if let Some(tie_break) = options.tie_break {
+        spell_checker = spell_checker.with_tie_break(tie_break);
+    }
+
+    if let Some(seed) = options.seed {
+        spell_checker = spell_checker.with_seed(seed);
+    }
+
+    if let Some(costs) = options.edit_costs {
+        spell_checker = spell_checker.with_edit_costs(costs);
+    }
+
+    if let Some(max_segments) = options.max_segments {
+        spell_checker = spell_checker.with_segmentation(max_segments);
+    }
+
+    if options.self_test {
+        let failures = spell_checker.self_test(true);
+        for (word, reason) in &failures {
+            println!("Self-test failure: {:?} {}", word, reason);
+        }
+        println!(
+            "Self-test finished: {} of {} entries failed",
+            failures.len(),
+            spell_checker.len()
+        );
+        return;
+    }
+
+    if options.freeform {
+        let document = options
+            .parse
+            .encoding
+            .read_file(word_file)
+            .and_then(|content| WordList::parse_freeform(&content, &options.parse));
+        let document = match document {
+            Ok(document) => document,
+            Err(e) => {
+                println!("Failed to read word file: {}", e);
+                return;
+            }
+        };
+        let corrected = spell_checker.correct_word_list(&document);
+        let write_options = match options.write_options() {
+            // Line breaks are tokens of the document; adding one would
+            // change its layout.
+            Ok(write_options) => WriteOptions {
+                trailing_newline: false,
+                ..write_options
+            },
+            Err(e) => {
+                println!("Failed to read word file: {}", e);
+                return;
+            }
+        };
+        match write_corrected_file(&[corrected], output_file, &write_options) {
+            Ok(_) => println!(
+                "Correction completed! Result saved to {}",
+                output_file.display()
+            ),
+            Err(e) => println!("Failed to write output file: {}", e),
+        }
+        return;
+    }
+
+    let word_lists = match read_word_lists(word_file, &options.parse) {
         Ok(lists) => {
             println!(
                 "Successfully parsed {} entries from {}",
                 lists.len(),
-                word_file
+                word_file.display()
             );
             lists
         }
@@ -216,30 +1109,359 @@ fn main() {
         }
     };
 
-    let corrected_lists: Vec<WordList> = word_lists
-        .iter()
-        .map(|word_list| spell_checker.correct_word_list(word_list))
-        .collect();
+    if let Some(severity) = options.check_duplicate_ids {
+        let duplicates = WordList::duplicate_ids(&word_lists);
+        let label = match severity {
+            Severity::Warn => "Warning",
+            Severity::Error => "Error",
+        };
+        for (id, line_numbers) in &duplicates {
+            let lines: Vec<String> = line_numbers.iter().map(usize::to_string).collect();
+            println!("{}: ID {} is used on lines {}", label, id, lines.join(", "));
+        }
+        if severity == Severity::Error && !duplicates.is_empty() {
+            return;
+        }
+    }
 
-    match write_corrected_file(&corrected_lists, output_file) {
-        Ok(_) => println!("Correction completed! Result saved to {}", output_file),
-        Err(e) => println!("Failed to write output file: {}", e),
+    if (options.from_line.is_some() || options.to_line.is_some())
+        && !word_lists
+            .iter()
+            .any(|word_list| options.in_line_range(word_list.line_number))
+    {
+        println!("Warning: no entries fall within the requested line range");
     }
-}
 
-fn write_corrected_file(word_lists: &[WordList], output_path: &str) -> Result<(), String> {
-    if let Some(parent) = Path::new(output_path).parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    if let Some(size) = options.preview {
+        let candidates: Vec<&WordList> = word_lists
+            .iter()
+            .filter(|word_list| options.in_line_range(word_list.line_number))
+            .collect();
+        let sample: Vec<&WordList> = if options.preview_sample {
+            spell_checker
+                .rng()
+                .sample_indices(candidates.len(), size)
+                .into_iter()
+                .map(|index| candidates[index])
+                .collect()
+        } else {
+            candidates.into_iter().take(size).collect()
+        };
+
+        let mut changed = 0;
+        for word_list in &sample {
+            let corrected = spell_checker.correct_word_list(word_list);
+            let (before, after) = (word_list.to_string(), corrected.to_string());
+            if before == after {
+                println!("  {}", before);
+            } else {
+                println!("- {}", before);
+                println!("+ {}", after);
+                changed += 1;
+            }
         }
+        println!(
+            "Preview: {} of {} lines would change; no output written",
+            changed,
+            sample.len()
+        );
+        return;
     }
 
-    let content = word_lists
-        .iter()
-        .map(|word_list| format!("{}\n", word_list))
-        .collect::<String>();
+    let jobs = options
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+
+    if let Some(runs) = options.benchmark {
+        let words: usize = word_lists
+            .iter()
+            .filter(|word_list| options.in_line_range(word_list.line_number))
+            .map(|word_list| {
+                word_list
+                    .tokens
+                    .iter()
+                    .filter(|token| matches!(token, Token::Word(_)))
+                    .count()
+            })
+            .sum();
 
-    fs::write(output_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+        let mut total = Duration::ZERO;
+        for run in 1..=runs {
+            let start = Instant::now();
+            spell_checker.correct_all_tracked(
+                &word_lists,
+                jobs,
+                |word_list| options.in_line_range(word_list.line_number),
+                &AtomicBool::new(false),
+            );
+            let elapsed = start.elapsed();
+            total += elapsed;
+            println!("Run {}: {:.2} ms", run, elapsed.as_secs_f64() * 1e3);
+        }
 
-    Ok(())
+        let seconds = total.as_secs_f64();
+        println!(
+            "Benchmark: {} words x {} runs on {} jobs; {:.0} words/s, {:.2} us/word on average",
+            words,
+            runs,
+            jobs,
+            (words * runs) as f64 / seconds,
+            seconds * 1e6 / (words * runs).max(1) as f64
+        );
+        return;
+    }
+
+    if options.coverage {
+        let report = CoverageReport::measure(&spell_checker, &word_lists);
+        println!(
+            "Coverage: {} of {} words ({:.1}%) are in the dictionary",
+            report.known_words,
+            report.total_words,
+            report.coverage() * 100.0
+        );
+        println!("Most common out-of-vocabulary words:");
+        for (word, count) in report.top_unknown(options.summary.unwrap_or(20)) {
+            println!("{:>6}  {}", count, word);
+        }
+        return;
+    }
+
+    if options.cross_check {
+        let mut disagreements = 0;
+        for word_list in &word_lists {
+            if !options.in_line_range(word_list.line_number) {
+                continue;
+            }
+            for d in spell_checker.cross_check_word_list(word_list) {
+                println!(
+                    "{} {}: fast '{}' (dist {}) vs reference '{}' (dist {})",
+                    word_list.id,
+                    d.word,
+                    d.fast,
+                    d.fast_distance,
+                    d.reference,
+                    d.reference_distance
+                );
+                disagreements += 1;
+            }
+        }
+        println!("Cross-check: {} disagreement(s)", disagreements);
+        return;
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&cancel);
+    // A second Ctrl-C stops right away instead of waiting for the lines in progress.
+    if let Err(e) = ctrlc::set_handler(move || {
+        if handler_flag.swap(true, Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+    }) {
+        println!("Warning: Ctrl-C will not stop the run cleanly: {}", e);
+    }
+
+    let select = |word_list: &WordList| options.in_line_range(word_list.line_number);
+    let run = if let Some(audit_log) = &options.audit_log {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(audit_log);
+        let mut file = match file {
+            Ok(file) => io::BufWriter::new(file),
+            Err(e) => {
+                println!("Failed to open audit log: {}", e);
+                return;
+            }
+        };
+        // Records are flushed entry by entry as each chunk finishes, so the
+        // log covers every corrected entry even if the run is cut short.
+        let mut records = 0;
+        let mut failure = None;
+        let run = spell_checker.correct_all_audited(
+            &word_lists,
+            jobs,
+            select,
+            &cancel,
+            |word_list, decisions| {
+                if failure.is_none() {
+                    match write_audit(&mut file, word_list, decisions) {
+                        Ok(()) => records += decisions.len(),
+                        Err(e) => failure = Some(e),
+                    }
+                }
+            },
+        );
+        if let Some(e) = failure {
+            println!("Failed to write audit log: {}", e);
+            return;
+        }
+        println!(
+            "Appended {} audit records to {}",
+            records,
+            audit_log.display()
+        );
+        run
+    } else {
+        spell_checker.correct_all_tracked(&word_lists, jobs, select, &cancel)
+    };
+    if run.completed() < word_lists.len() {
+        println!(
+            "Interrupted: {} of {} entries completed; the rest are written unchanged",
+            run.completed(),
+            word_lists.len()
+        );
+    }
+
+    let mut summary = CorrectionSummary::new();
+    let mut buckets = DistanceBuckets::new();
+    let mut bucket_lines: [String; 4] = Default::default();
+    let mut corrected_lists: Vec<WordList> = Vec::with_capacity(word_lists.len());
+    let mut doubled_words = 0;
+    let mut learnable: Vec<(String, String)> = Vec::new();
+    for ((mut corrected, changes), finished) in run.results.into_iter().zip(run.finished) {
+        for (original, replacement) in &changes {
+            summary.add(original, replacement);
+            if options.learn {
+                learnable.push((original.clone(), replacement.clone()));
+            }
+            if options.bucket_by_distance {
+                let distance = spell_checker.edit_distance(original, replacement);
+                buckets.add(distance);
+                if options.bucket_files {
+                    bucket_lines[DistanceBuckets::bucket(distance)].push_str(&format!(
+                        "{} {} -> {}\n",
+                        corrected.id, original, replacement
+                    ));
+                }
+            }
+        }
+        // Entries passed through after an interrupt must stay as written.
+        if !finished || !options.in_line_range(corrected.line_number) {
+            corrected_lists.push(corrected);
+            continue;
+        }
+        if options.dedup_words {
+            doubled_words += corrected.dedup_words();
+        }
+        if let Some(observed) = observed.as_mut() {
+            observed.observe_word_list(&corrected, &spell_checker);
+        }
+        corrected_lists.push(corrected);
+    }
+
+    if options.dedup_words {
+        println!("Removed {} doubled words", doubled_words);
+    }
+
+    if let Some(size) = options.summary {
+        println!("{} corrections made; most frequent:", summary.total());
+        for (original, corrected, count) in summary.top(size) {
+            println!("{:>6}  {} -> {}", count, original, corrected);
+        }
+    }
+
+    if options.bucket_by_distance {
+        println!("Corrections by edit distance:");
+        for (label, count) in DistanceBuckets::LABELS.iter().zip(buckets.counts()) {
+            println!("{:>4}  {}", label, count);
+        }
+    }
+
+    if options.stats {
+        let stats = spell_checker.comparison_stats();
+        println!(
+            "Dictionary comparisons: {} over {} searches ({:.1} per search)",
+            stats.comparisons,
+            stats.searches,
+            stats.average()
+        );
+        let memory = spell_checker.memory_report();
+        println!("Estimated memory: {} KiB", memory.total() / 1024);
+        for (label, bytes) in memory.entries() {
+            if bytes > 0 {
+                println!("{:>10} KiB  {}", bytes / 1024, label);
+            }
+        }
+    }
+
+    let write_options = match options.write_options() {
+        Ok(write_options) => write_options,
+        Err(e) => {
+            println!("Failed to read word file: {}", e);
+            return;
+        }
+    };
+
+    if options.routes.is_empty() {
+        match write_corrected_file(&corrected_lists, output_file, &write_options) {
+            Ok(_) => println!(
+                "Correction completed! Result saved to {}",
+                output_file.display()
+            ),
+            Err(e) => {
+                println!("Failed to write output file: {}", e);
+                return;
+            }
+        }
+    } else {
+        match write_routed_files(
+            &corrected_lists,
+            &options.routes,
+            output_file,
+            &write_options,
+        ) {
+            Ok(written) => {
+                println!("Correction completed! Results saved to:");
+                for (path, lines) in written {
+                    println!("{:>6}  {}", lines, path.display());
+                }
+            }
+            Err(e) => {
+                println!("Failed to write output file: {}", e);
+                return;
+            }
+        }
+    }
+
+    if options.learn
+        && let Some(learned) = learned.as_mut()
+    {
+        let confirmed = learned.confirm_with(
+            learnable
+                .iter()
+                .map(|(original, replacement)| (original.as_str(), replacement.as_str())),
+            ask_to_learn,
+        );
+        println!("Confirmed {} new corrections", confirmed);
+        match learned.save() {
+            Ok(()) => println!(
+                "Saved {} learned corrections to {}",
+                learned.len(),
+                learned.path().display()
+            ),
+            Err(e) => println!("Failed to save learned corrections: {}", e),
+        }
+    }
+
+    if let Some(observed) = &observed {
+        match observed.save() {
+            Ok(()) => println!(
+                "Saved {} observed word frequencies to {}",
+                observed.len(),
+                observed.path().display()
+            ),
+            Err(e) => println!("Failed to save observed frequencies: {}", e),
+        }
+    }
+
+    // Written after the main output, whose directory now exists.
+    if options.bucket_files {
+        for (label, lines) in DistanceBuckets::LABELS.iter().zip(&bucket_lines) {
+            let path = output_file.with_extension(format!("distance-{}.txt", label));
+            if let Err(e) = fs::write(&path, lines) {
+                println!("Failed to write bucket file {}: {}", path.display(), e);
+                return;
+            }
+        }
+    }
 }
@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+/// Estimated heap bytes held by a [`SpellChecker`](crate::SpellChecker),
+/// split by structure. Estimates count each collection's capacity times its
+/// element size plus the bytes of the strings it owns; allocator overhead
+/// and hash table control bytes beyond one per slot are not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub dictionary: usize,
+    /// Per-entry script tags.
+    pub scripts: usize,
+    /// Case-folded lookup index.
+    pub folded: usize,
+    pub frequencies: usize,
+    pub stems: usize,
+    pub phonetic_keys: usize,
+    /// Forced, learned and forbidden substitutions.
+    pub substitutions: usize,
+    /// As reported by the [`CandidateSource`](crate::CandidateSource).
+    pub candidate_source: usize,
+    pub cache: usize,
+}
+
+impl MemoryReport {
+    pub fn total(&self) -> usize {
+        self.entries().iter().map(|&(_, bytes)| bytes).sum()
+    }
+
+    /// Every structure with its label, in a fixed order for printing.
+    pub fn entries(&self) -> [(&'static str, usize); 9] {
+        [
+            ("dictionary", self.dictionary),
+            ("scripts", self.scripts),
+            ("folded index", self.folded),
+            ("frequencies", self.frequencies),
+            ("stems", self.stems),
+            ("phonetic keys", self.phonetic_keys),
+            ("substitutions", self.substitutions),
+            ("candidate source", self.candidate_source),
+            ("cache", self.cache),
+        ]
+    }
+}
+
+/// Heap bytes owned by a value beyond its own `size_of`.
+pub(crate) trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl HeapSize for usize {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for u64 {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<A: HeapSize, B: HeapSize> HeapSize for (A, B) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl HeapSize for crate::audit::Strategy {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: HeapSize> HeapSize for HashSet<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * (size_of::<T>() + 1) + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<K: HeapSize, V: HeapSize> HeapSize for HashMap<K, V> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * (size_of::<(K, V)>() + 1)
+            + self
+                .iter()
+                .map(|(key, value)| key.heap_size() + value.heap_size())
+                .sum::<usize>()
+    }
+}
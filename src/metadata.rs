@@ -0,0 +1,84 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::SpellError;
+
+/// What is needed to reproduce a run: the tool version, the dictionary and
+/// its content hash, the command-line options and when the run happened.
+///
+/// Displays as a front matter block holding only `#` comments, so it can be
+/// prepended to output that is read back in: the parser skips the block and
+/// no settings are taken from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunMetadata {
+    pub version: &'static str,
+    pub dictionary: PathBuf,
+    /// 64-bit FNV-1a hash of the dictionary file's bytes.
+    pub dictionary_hash: u64,
+    pub options: Vec<String>,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// FNV-1a: tiny, dependency-free and stable across platforms and releases,
+/// which is all an audit trail needs. Not meant to resist tampering.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl RunMetadata {
+    /// Hashes `dictionary` and stamps the current time.
+    pub fn collect<P, I>(dictionary: P, options: I) -> Result<Self, SpellError>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = String>,
+    {
+        let dictionary = dictionary.as_ref();
+        let bytes = fs::read(dictionary)
+            .map_err(|e| SpellError::Io(format!("Failed to hash dictionary: {}", e)))?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        Ok(RunMetadata {
+            version: crate::VERSION,
+            dictionary: dictionary.to_path_buf(),
+            dictionary_hash: fnv1a(&bytes),
+            options: options.into_iter().collect(),
+            timestamp,
+        })
+    }
+}
+
+impl fmt::Display for RunMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = self
+            .options
+            .iter()
+            .map(|option| {
+                if option.is_empty() || option.contains(char::is_whitespace) {
+                    format!("{:?}", option)
+                } else {
+                    option.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(f, "---")?;
+        writeln!(f, "# homework_03 {}", self.version)?;
+        writeln!(
+            f,
+            "# dictionary: {} (fnv1a64 {:016x})",
+            self.dictionary.display(),
+            self.dictionary_hash
+        )?;
+        writeln!(f, "# options: {}", options)?;
+        writeln!(f, "# timestamp: {}", self.timestamp)?;
+        writeln!(f, "---")
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::SpellError;
+use crate::output::{read_if_exists, save};
+use crate::spell_checker::SpellChecker;
+use crate::word_list::{Token, WordList};
+
+/// Word counts observed in a corpus's corrected output, persisted between
+/// runs in the `word count` format of a frequency list. Each run adds the
+/// dictionary words it wrote; passing [`counts`] to
+/// [`SpellChecker::with_frequencies`] on the next run lets the frequency
+/// tie-break favour the entries this corpus actually uses. The counts only
+/// ever grow, so a word observed often keeps its boost until the file is
+/// edited or removed.
+///
+/// [`counts`]: ObservedFrequencies::counts
+#[derive(Debug, Clone)]
+pub struct ObservedFrequencies {
+    path: PathBuf,
+    counts: HashMap<String, u64>,
+}
+
+impl ObservedFrequencies {
+    /// Loads the counts observed so far from `path`. A missing file is the
+    /// start of a new store, not an error.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, SpellError> {
+        let path = path.into();
+        let counts = match read_if_exists(&path, "Failed to load observed frequencies")? {
+            Some(content) => SpellChecker::parse_frequencies(&content)?,
+            None => HashMap::new(),
+        };
+
+        Ok(ObservedFrequencies { path, counts })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn counts(&self) -> &HashMap<String, u64> {
+        &self.counts
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Adds one occurrence of `word`. Returns `false` without counting
+    /// anything for words containing whitespace, which the file format
+    /// cannot hold.
+    pub fn observe(&mut self, word: &str) -> bool {
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return false;
+        }
+
+        *self.counts.entry(word.to_string()).or_insert(0) += 1;
+        true
+    }
+
+    /// Observes every word token of `word_list`, typically a corrected one,
+    /// that `checker` knows. Returns how many were counted.
+    pub fn observe_word_list(&mut self, word_list: &WordList, checker: &SpellChecker) -> usize {
+        let mut observed = 0;
+        for token in &word_list.tokens {
+            if let Token::Word(word) = token
+                && checker.contains_word(word)
+                && self.observe(word)
+            {
+                observed += 1;
+            }
+        }
+        observed
+    }
+
+    /// Writes every count back to the file, sorted by word so the file
+    /// diffs cleanly between runs. The file is replaced atomically, like
+    /// [`LearnedCorrections::save`](crate::LearnedCorrections::save).
+    pub fn save(&self) -> Result<(), SpellError> {
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort();
+        let content: String = counts
+            .into_iter()
+            .map(|(word, count)| format!("{} {}\n", word, count))
+            .collect();
+
+        save(
+            &self.path,
+            content.as_bytes(),
+            true,
+            "Failed to save observed frequencies",
+        )
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::encoding::{Encoding, strip_bom};
+use crate::error::SpellError;
+use crate::trace;
+use crate::word_list::{SeparatorMerge, Token, WordList};
+
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Terminate the last line with `\n`. Every other line always gets one.
+    pub trailing_newline: bool,
+    /// Encoding of the written file. Characters it cannot represent fail the write.
+    pub encoding: Encoding,
+    /// Collapse runs of separators such as `"  "` or `"//"`. Off by default so
+    /// the output reproduces the input spacing.
+    pub merge_separators: Option<SeparatorMerge>,
+    /// Drop separators after the last word of each line. Off by default so
+    /// the output reproduces the input.
+    pub trim_trailing_separators: bool,
+    /// Written before the first entry, such as a
+    /// [`RunMetadata`](crate::RunMetadata) block. It must be something the
+    /// parser skips, or the output stops being valid input.
+    pub header: Option<String>,
+    /// Write only the word tokens, without IDs or separators. Lossy: the
+    /// output cannot be read back as input or mapped to its lines.
+    pub words_only: Option<WordsOnly>,
+    /// Write to a temporary file next to the output and rename it into
+    /// place, so a failed or interrupted write leaves the old file intact.
+    pub atomic: bool,
+    /// The text the entries were read from. When set, each entry replaces
+    /// its own line and every other line (front matter, blank lines, lines
+    /// skipped in lenient mode) is copied unchanged, so the output keeps the
+    /// input's layout. Ignored with [`words_only`](Self::words_only).
+    pub source: Option<String>,
+}
+
+/// Layout of [`WriteOptions::words_only`] output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordsOnly {
+    /// Each entry's words joined by single spaces, one entry per line.
+    Joined,
+    /// Every word on its own line.
+    PerLine,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            trailing_newline: true,
+            encoding: Encoding::Utf8,
+            merge_separators: None,
+            trim_trailing_separators: false,
+            header: None,
+            words_only: None,
+            atomic: false,
+            source: None,
+        }
+    }
+}
+
+/// Returns whether the file at `path` ends with a newline, so output can
+/// mirror the input. An empty file counts as not ending with one.
+pub fn ends_with_newline<P: AsRef<Path>>(path: P) -> Result<bool, SpellError> {
+    let mut file =
+        File::open(path).map_err(|e| SpellError::Io(format!("Failed to read: {}", e)))?;
+
+    if file
+        .seek(SeekFrom::End(0))
+        .map_err(|e| SpellError::Io(format!("Failed to read: {}", e)))?
+        == 0
+    {
+        return Ok(false);
+    }
+
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::End(-1))
+        .and_then(|_| file.read_exact(&mut last))
+        .map_err(|e| SpellError::Io(format!("Failed to read: {}", e)))?;
+
+    Ok(last[0] == b'\n')
+}
+
+pub fn write_corrected_file<P: AsRef<Path>>(
+    word_lists: &[WordList],
+    output_path: P,
+    options: &WriteOptions,
+) -> Result<(), SpellError> {
+    let output_path = output_path.as_ref();
+    let mut span = trace::span("write_output");
+    span.record("lines", word_lists.len());
+
+    let rendered = word_lists.iter().map(|word_list| {
+        if let Some(layout) = options.words_only {
+            let words = word_list.tokens.iter().filter_map(|token| match token {
+                Token::Word(word) => Some(word.as_str()),
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            });
+            return match layout {
+                WordsOnly::Joined => format!("{}\n", words.collect::<Vec<_>>().join(" ")),
+                WordsOnly::PerLine => words.map(|word| format!("{}\n", word)).collect(),
+            };
+        }
+
+        if options.merge_separators.is_none() && !options.trim_trailing_separators {
+            return format!("{}\n", word_list);
+        }
+
+        let mut word_list = word_list.clone();
+        if let Some(keep) = options.merge_separators {
+            word_list.merge_separators(keep);
+        }
+        if options.trim_trailing_separators {
+            word_list.trim_trailing_separators();
+        }
+        format!("{}\n", word_list)
+    });
+
+    let mut content = match &options.source {
+        Some(source) if options.words_only.is_none() => {
+            let mut entries = word_lists
+                .iter()
+                .map(WordList::line_number)
+                .zip(rendered)
+                .peekable();
+            let mut content = String::new();
+            for (i, line) in strip_bom(source).lines().enumerate() {
+                match entries.next_if(|(line_number, _)| *line_number == i + 1) {
+                    Some((_, entry)) => content.push_str(&entry),
+                    None => {
+                        content.push_str(line);
+                        content.push('\n');
+                    }
+                }
+            }
+            content
+        }
+        _ => rendered.collect::<String>(),
+    };
+
+    if !options.trailing_newline {
+        content.pop();
+    }
+
+    if let Some(header) = &options.header {
+        content.insert_str(0, header);
+    }
+
+    let bytes = options.encoding.encode(content)?;
+    save(output_path, &bytes, options.atomic, "Failed to write file")
+}
+
+/// Reads the file at `path`, or `None` if it does not exist yet, as for a
+/// store that has never been saved. `failure` starts the error message.
+pub(crate) fn read_if_exists(path: &Path, failure: &str) -> Result<Option<String>, SpellError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|e| SpellError::Io(format!("{}: {}", failure, e)))
+}
+
+/// Writes `bytes` to `path`, creating its directory first, and through
+/// [`write_atomically`] if `atomic`. `failure` starts the error message of
+/// a failed write.
+pub(crate) fn save(
+    path: &Path,
+    bytes: &[u8],
+    atomic: bool,
+    failure: &str,
+) -> Result<(), SpellError> {
+    // A bare file name has an empty parent, which means the current directory.
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)
+            .map_err(|e| SpellError::Io(format!("Failed to create directory: {}", e)))?;
+    }
+
+    if atomic {
+        write_atomically(path, bytes)
+    } else {
+        fs::write(path, bytes)
+    }
+    .map_err(|e| SpellError::Io(format!("{}: {}", failure, e)))
+}
+
+/// Writes `bytes` to a hidden temporary file in `path`'s directory, then
+/// renames it over `path`. The rename is atomic on the same file system, so
+/// readers see either the old content or the new, never a partial file. An
+/// existing file's permissions are carried over.
+fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().unwrap_or(path.as_os_str());
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Writes each list to the path of the first rule whose ID prefix matches its
+/// ID, or to `default_path` if none does, keeping the input order within each
+/// file. Every rule's file and the default file are written even if no line
+/// goes there, so no stale output from an earlier run survives. Returns every
+/// written path with the number of lines it received.
+pub fn write_routed_files<P: AsRef<Path>>(
+    word_lists: &[WordList],
+    rules: &[(String, PathBuf)],
+    default_path: P,
+    options: &WriteOptions,
+) -> Result<Vec<(PathBuf, usize)>, SpellError> {
+    let mut routes: Vec<(PathBuf, Vec<WordList>)> = rules
+        .iter()
+        .map(|(_, path)| path.clone())
+        .chain([default_path.as_ref().to_path_buf()])
+        .fold(Vec::new(), |mut routes, path| {
+            if !routes.iter().any(|(existing, _)| *existing == path) {
+                routes.push((path, Vec::new()));
+            }
+            routes
+        });
+
+    for word_list in word_lists {
+        let path = rules
+            .iter()
+            .find(|(prefix, _)| word_list.id.starts_with(prefix.as_str()))
+            .map_or(default_path.as_ref(), |(_, path)| path.as_path());
+        if let Some((_, lists)) = routes.iter_mut().find(|(existing, _)| existing == path) {
+            lists.push(word_list.clone());
+        }
+    }
+
+    routes
+        .into_iter()
+        .map(|(path, lists)| {
+            write_corrected_file(&lists, &path, options)?;
+            Ok((path, lists.len()))
+        })
+        .collect()
+}
@@ -0,0 +1,14 @@
+use std::fs;
+use std::path::Path;
+
+/// Whether `a` and `b` name the same file, comparing canonical paths so that
+/// `words.txt` and `./problem/../words.txt` match. If either path cannot be
+/// canonicalized (e.g. it does not exist yet), the paths are compared as
+/// given.
+pub fn same_file<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> bool {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
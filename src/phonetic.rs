@@ -0,0 +1,645 @@
+/// Algorithms producing "sounds like" keys for the phonetic fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phonetic {
+    Soundex,
+    /// Lawrence Philips' Double Metaphone, using both the primary and the
+    /// alternate key.
+    DoubleMetaphone,
+}
+
+impl Phonetic {
+    pub fn from_name(name: &str) -> Option<Phonetic> {
+        match name.to_ascii_lowercase().as_str() {
+            "soundex" => Some(Phonetic::Soundex),
+            "metaphone" | "double-metaphone" => Some(Phonetic::DoubleMetaphone),
+            _ => None,
+        }
+    }
+
+    /// Every key of `word`; two words sound alike if they share one.
+    pub fn keys(&self, word: &str) -> Vec<String> {
+        let keys = match self {
+            Phonetic::Soundex => vec![soundex(word)],
+            Phonetic::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(word);
+                std::iter::once(primary).chain(alternate).collect()
+            }
+        };
+
+        keys.into_iter().filter(|key| !key.is_empty()).collect()
+    }
+}
+
+/// American Soundex: the first letter followed by three digits ("Robert" -> "R163").
+pub fn soundex(word: &str) -> String {
+    fn code(c: char) -> Option<char> {
+        match c {
+            'B' | 'F' | 'P' | 'V' => Some('1'),
+            'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+            'D' | 'T' => Some('3'),
+            'L' => Some('4'),
+            'M' | 'N' => Some('5'),
+            'R' => Some('6'),
+            _ => None,
+        }
+    }
+
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+
+    let mut key = String::from(first);
+    let mut previous = code(first);
+    for c in letters {
+        let current = code(c);
+        if current.is_some() && current != previous {
+            key.extend(current);
+            if key.len() == 4 {
+                break;
+            }
+        }
+        // H and W do not separate letters with the same code; vowels do.
+        if c != 'H' && c != 'W' {
+            previous = current;
+        }
+    }
+
+    while key.len() < 4 {
+        key.push('0');
+    }
+    key
+}
+
+const MAX_KEY_LENGTH: usize = 4;
+
+/// Double Metaphone keys of `word`: the primary key and, if it differs, an
+/// alternate key for other plausible pronunciations ("Schmidt" -> "XMT",
+/// "SMT"). Keys are at most four characters; `0` stands for "th".
+pub fn double_metaphone(word: &str) -> (String, Option<String>) {
+    let mut state = Metaphone {
+        word: word.to_uppercase().chars().collect(),
+        primary: String::new(),
+        alternate: String::new(),
+    };
+    state.encode();
+
+    let Metaphone {
+        mut primary,
+        mut alternate,
+        ..
+    } = state;
+    primary.truncate(MAX_KEY_LENGTH);
+    alternate.truncate(MAX_KEY_LENGTH);
+
+    if alternate == primary {
+        (primary, None)
+    } else {
+        (primary, Some(alternate))
+    }
+}
+
+struct Metaphone {
+    word: Vec<char>,
+    primary: String,
+    alternate: String,
+}
+
+impl Metaphone {
+    fn at(&self, index: isize) -> char {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.word.get(index))
+            .copied()
+            .unwrap_or('\0')
+    }
+
+    /// Whether the `length` characters at `start` equal one of `options`.
+    fn matches(&self, start: isize, length: usize, options: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else {
+            return false;
+        };
+        let Some(slice) = self.word.get(start..start + length) else {
+            return false;
+        };
+        options
+            .iter()
+            .any(|option| option.chars().eq(slice.iter().copied()))
+    }
+
+    fn is_vowel(&self, index: isize) -> bool {
+        matches!(self.at(index), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn is_slavo_germanic(&self) -> bool {
+        let word: String = self.word.iter().collect();
+        word.contains('W') || word.contains('K') || word.contains("CZ") || word.contains("WITZ")
+    }
+
+    fn add(&mut self, both: &str) {
+        self.add_pair(both, both);
+    }
+
+    fn add_pair(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn is_germanic(&self) -> bool {
+        self.matches(0, 4, &["VAN ", "VON "]) || self.matches(0, 3, &["SCH"])
+    }
+
+    fn encode(&mut self) {
+        let length = self.word.len() as isize;
+        let last = length - 1;
+        let slavo_germanic = self.is_slavo_germanic();
+        let mut current: isize = 0;
+
+        if self.matches(0, 2, &["GN", "KN", "PN", "WR", "PS"]) {
+            current += 1;
+        }
+        if self.at(0) == 'X' {
+            self.add("S");
+            current += 1;
+        }
+
+        while current < length
+            && (self.primary.len() < MAX_KEY_LENGTH || self.alternate.len() < MAX_KEY_LENGTH)
+        {
+            let next = self.at(current + 1);
+            current += match self.at(current) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if current == 0 {
+                        self.add("A");
+                    }
+                    1
+                }
+                'B' => {
+                    self.add("P");
+                    if next == 'B' { 2 } else { 1 }
+                }
+                'Ç' => {
+                    self.add("S");
+                    1
+                }
+                'C' => self.encode_c(current),
+                'D' => {
+                    if self.matches(current, 2, &["DG"]) {
+                        if self.matches(current + 2, 1, &["I", "E", "Y"]) {
+                            self.add("J");
+                            3
+                        } else {
+                            self.add("TK");
+                            2
+                        }
+                    } else {
+                        self.add("T");
+                        if self.matches(current, 2, &["DT", "DD"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'F' => {
+                    self.add("F");
+                    if next == 'F' { 2 } else { 1 }
+                }
+                'G' => self.encode_g(current, slavo_germanic),
+                // H is only sounded between vowels or at the start before one.
+                'H' if (current == 0 || self.is_vowel(current - 1))
+                    && self.is_vowel(current + 1) =>
+                {
+                    self.add("H");
+                    2
+                }
+                'J' => self.encode_j(current, last, slavo_germanic),
+                'K' => {
+                    self.add("K");
+                    if next == 'K' { 2 } else { 1 }
+                }
+                'L' => {
+                    if next == 'L' {
+                        if (current == length - 3
+                            && self.matches(current - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+                            || ((self.matches(last - 1, 2, &["AS", "OS"])
+                                || self.matches(last, 1, &["A", "O"]))
+                                && self.matches(current - 1, 4, &["ALLE"]))
+                        {
+                            self.add_pair("L", "");
+                        } else {
+                            self.add("L");
+                        }
+                        2
+                    } else {
+                        self.add("L");
+                        1
+                    }
+                }
+                'M' => {
+                    self.add("M");
+                    if (self.matches(current - 1, 3, &["UMB"])
+                        && (current + 1 == last || self.matches(current + 2, 2, &["ER"])))
+                        || next == 'M'
+                    {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'N' => {
+                    self.add("N");
+                    if next == 'N' { 2 } else { 1 }
+                }
+                'Ñ' => {
+                    self.add("N");
+                    1
+                }
+                'P' => {
+                    if next == 'H' {
+                        self.add("F");
+                        2
+                    } else {
+                        self.add("P");
+                        if next == 'P' || next == 'B' { 2 } else { 1 }
+                    }
+                }
+                'Q' => {
+                    self.add("K");
+                    if next == 'Q' { 2 } else { 1 }
+                }
+                'R' => {
+                    if current == last
+                        && !slavo_germanic
+                        && self.matches(current - 2, 2, &["IE"])
+                        && !self.matches(current - 4, 2, &["ME", "MA"])
+                    {
+                        self.add_pair("", "R");
+                    } else {
+                        self.add("R");
+                    }
+                    if next == 'R' { 2 } else { 1 }
+                }
+                'S' => self.encode_s(current, last, slavo_germanic),
+                'T' => {
+                    if self.matches(current, 4, &["TION"])
+                        || self.matches(current, 3, &["TIA", "TCH"])
+                    {
+                        self.add("X");
+                        3
+                    } else if self.matches(current, 2, &["TH"])
+                        || self.matches(current, 3, &["TTH"])
+                    {
+                        if self.matches(current + 2, 2, &["OM", "AM"]) || self.is_germanic() {
+                            self.add("T");
+                        } else {
+                            self.add_pair("0", "T");
+                        }
+                        2
+                    } else {
+                        self.add("T");
+                        if next == 'T' || next == 'D' { 2 } else { 1 }
+                    }
+                }
+                'V' => {
+                    self.add("F");
+                    if next == 'V' { 2 } else { 1 }
+                }
+                'W' => self.encode_w(current, last),
+                'X' => {
+                    if !(current == last
+                        && (self.matches(current - 3, 3, &["IAU", "EAU"])
+                            || self.matches(current - 2, 2, &["AU", "OU"])))
+                    {
+                        self.add("KS");
+                    }
+                    if next == 'C' || next == 'X' { 2 } else { 1 }
+                }
+                'Z' => {
+                    if next == 'H' {
+                        self.add("J");
+                        2
+                    } else {
+                        if self.matches(current + 1, 2, &["ZO", "ZI", "ZA"])
+                            || (slavo_germanic && current > 0 && self.at(current - 1) != 'T')
+                        {
+                            self.add_pair("S", "TS");
+                        } else {
+                            self.add("S");
+                        }
+                        if next == 'Z' { 2 } else { 1 }
+                    }
+                }
+                _ => 1,
+            };
+        }
+    }
+
+    fn encode_c(&mut self, current: isize) -> isize {
+        // Germanic "ACH" as in "Bacher", "Macher".
+        if current > 1
+            && !self.is_vowel(current - 2)
+            && self.matches(current - 1, 3, &["ACH"])
+            && self.at(current + 2) != 'I'
+            && (self.at(current + 2) != 'E' || self.matches(current - 2, 6, &["BACHER", "MACHER"]))
+        {
+            self.add("K");
+            return 2;
+        }
+        if current == 0 && self.matches(current, 6, &["CAESAR"]) {
+            self.add("S");
+            return 2;
+        }
+        if self.matches(current, 4, &["CHIA"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.matches(current, 2, &["CH"]) {
+            if current > 0 && self.matches(current, 4, &["CHAE"]) {
+                self.add_pair("K", "X");
+            } else if (current == 0
+                && (self.matches(current + 1, 5, &["HARAC", "HARIS"])
+                    || self.matches(current + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+                && !self.matches(0, 5, &["CHORE"]))
+                || self.is_germanic()
+                || self.matches(current - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+                || self.matches(current + 2, 1, &["T", "S"])
+                || ((self.matches(current - 1, 1, &["A", "O", "U", "E"]) || current == 0)
+                    && self.matches(
+                        current + 2,
+                        1,
+                        &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                    ))
+            {
+                self.add("K");
+            } else if current > 0 {
+                if self.matches(0, 2, &["MC"]) {
+                    self.add("K");
+                } else {
+                    self.add_pair("X", "K");
+                }
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        if self.matches(current, 2, &["CZ"]) && !self.matches(current - 2, 4, &["WICZ"]) {
+            self.add_pair("S", "X");
+            return 2;
+        }
+        if self.matches(current + 1, 3, &["CIA"]) {
+            self.add("X");
+            return 3;
+        }
+        if self.matches(current, 2, &["CC"]) && !(current == 1 && self.at(0) == 'M') {
+            if self.matches(current + 2, 1, &["I", "E", "H"])
+                && !self.matches(current + 2, 2, &["HU"])
+            {
+                if (current == 1 && self.at(current - 1) == 'A')
+                    || self.matches(current - 1, 5, &["UCCEE", "UCCES"])
+                {
+                    self.add("KS");
+                } else {
+                    self.add("X");
+                }
+                return 3;
+            }
+            self.add("K");
+            return 2;
+        }
+        if self.matches(current, 2, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            return 2;
+        }
+        if self.matches(current, 2, &["CI", "CE", "CY"]) {
+            if self.matches(current, 3, &["CIO", "CIE", "CIA"]) {
+                self.add_pair("S", "X");
+            } else {
+                self.add("S");
+            }
+            return 2;
+        }
+
+        self.add("K");
+        if self.matches(current + 1, 2, &[" C", " Q", " G"]) {
+            3
+        } else if self.matches(current + 1, 1, &["C", "K", "Q"])
+            && !self.matches(current + 1, 2, &["CE", "CI"])
+        {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_g(&mut self, current: isize, slavo_germanic: bool) -> isize {
+        let next = self.at(current + 1);
+
+        if next == 'H' {
+            if current > 0 && !self.is_vowel(current - 1) {
+                self.add("K");
+            } else if current == 0 {
+                if self.at(current + 2) == 'I' {
+                    self.add("J");
+                } else {
+                    self.add("K");
+                }
+            } else if (current > 1 && self.matches(current - 2, 1, &["B", "H", "D"]))
+                || (current > 2 && self.matches(current - 3, 1, &["B", "H", "D"]))
+                || (current > 3 && self.matches(current - 4, 1, &["B", "H"]))
+            {
+                // Silent, as in "bough" or "hugh".
+            } else if current > 2
+                && self.at(current - 1) == 'U'
+                && self.matches(current - 3, 1, &["C", "G", "L", "R", "T"])
+            {
+                // "laugh", "cough", "rough".
+                self.add("F");
+            } else if current > 0 && self.at(current - 1) != 'I' {
+                self.add("K");
+            }
+            return 2;
+        }
+
+        if next == 'N' {
+            if current == 1 && self.is_vowel(0) && !slavo_germanic {
+                self.add_pair("KN", "N");
+            } else if !self.matches(current + 2, 2, &["EY"]) && next != 'Y' && !slavo_germanic {
+                self.add_pair("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            return 2;
+        }
+
+        if self.matches(current + 1, 2, &["LI"]) && !slavo_germanic {
+            self.add_pair("KL", "L");
+            return 2;
+        }
+
+        if current == 0
+            && (next == 'Y'
+                || self.matches(
+                    current + 1,
+                    2,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            self.add_pair("K", "J");
+            return 2;
+        }
+
+        if (self.matches(current + 1, 2, &["ER"]) || next == 'Y')
+            && !self.matches(0, 6, &["DANGER", "RANGER", "MANGER"])
+            && !self.matches(current - 1, 1, &["E", "I"])
+            && !self.matches(current - 1, 3, &["RGY", "OGY"])
+        {
+            self.add_pair("K", "J");
+            return 2;
+        }
+
+        if self.matches(current + 1, 1, &["E", "I", "Y"])
+            || self.matches(current - 1, 4, &["AGGI", "OGGI"])
+        {
+            if self.is_germanic() || self.matches(current + 1, 2, &["ET"]) {
+                self.add("K");
+            } else if self.matches(current + 1, 4, &["IER "]) {
+                self.add("J");
+            } else {
+                self.add_pair("J", "K");
+            }
+            return 2;
+        }
+
+        self.add("K");
+        if next == 'G' { 2 } else { 1 }
+    }
+
+    fn encode_j(&mut self, current: isize, last: isize, slavo_germanic: bool) -> isize {
+        if self.matches(current, 4, &["JOSE"]) || self.matches(0, 4, &["SAN "]) {
+            if (current == 0 && self.at(current + 4) == ' ') || self.matches(0, 4, &["SAN "]) {
+                self.add("H");
+            } else {
+                self.add_pair("J", "H");
+            }
+            return 1;
+        }
+
+        if current == 0 {
+            self.add_pair("J", "A");
+        } else if self.is_vowel(current - 1)
+            && !slavo_germanic
+            && matches!(self.at(current + 1), 'A' | 'O')
+        {
+            self.add_pair("J", "H");
+        } else if current == last {
+            self.add_pair("J", "");
+        } else if !self.matches(current + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.matches(current - 1, 1, &["S", "K", "L"])
+        {
+            self.add("J");
+        }
+
+        if self.at(current + 1) == 'J' { 2 } else { 1 }
+    }
+
+    fn encode_s(&mut self, current: isize, last: isize, slavo_germanic: bool) -> isize {
+        if self.matches(current - 1, 3, &["ISL", "YSL"]) {
+            // Silent, as in "island" or "carlysle".
+            return 1;
+        }
+        if current == 0 && self.matches(current, 5, &["SUGAR"]) {
+            self.add_pair("X", "S");
+            return 1;
+        }
+        if self.matches(current, 2, &["SH"]) {
+            if self.matches(current + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+        if self.matches(current, 3, &["SIO", "SIA"]) || self.matches(current, 4, &["SIAN"]) {
+            if slavo_germanic {
+                self.add("S");
+            } else {
+                self.add_pair("S", "X");
+            }
+            return 3;
+        }
+        if (current == 0 && self.matches(current + 1, 1, &["M", "N", "L", "W"]))
+            || self.matches(current + 1, 1, &["Z"])
+        {
+            self.add_pair("S", "X");
+            return if self.at(current + 1) == 'Z' { 2 } else { 1 };
+        }
+        if self.matches(current, 2, &["SC"]) {
+            if self.at(current + 2) == 'H' {
+                if self.matches(current + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                    if self.matches(current + 3, 2, &["ER", "EN"]) {
+                        self.add_pair("X", "SK");
+                    } else {
+                        self.add("SK");
+                    }
+                } else if current == 0 && !self.is_vowel(3) && self.at(3) != 'W' {
+                    self.add_pair("X", "S");
+                } else {
+                    self.add("X");
+                }
+            } else if self.matches(current + 2, 1, &["I", "E", "Y"]) {
+                self.add("S");
+            } else {
+                self.add("SK");
+            }
+            return 3;
+        }
+
+        if current == last && self.matches(current - 2, 2, &["AI", "OI"]) {
+            // French endings such as "resnais".
+            self.add_pair("", "S");
+        } else {
+            self.add("S");
+        }
+        if matches!(self.at(current + 1), 'S' | 'Z') {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_w(&mut self, current: isize, last: isize) -> isize {
+        if self.matches(current, 2, &["WR"]) {
+            self.add("R");
+            return 2;
+        }
+
+        if current == 0 && (self.is_vowel(current + 1) || self.matches(current, 2, &["WH"])) {
+            if self.is_vowel(current + 1) {
+                self.add_pair("A", "F");
+            } else {
+                self.add("A");
+            }
+        }
+
+        if (current == last && self.is_vowel(current - 1))
+            || self.matches(current - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.matches(0, 3, &["SCH"])
+        {
+            self.add_pair("", "F");
+            return 1;
+        }
+
+        if self.matches(current, 4, &["WICZ", "WITZ"]) {
+            self.add_pair("TS", "FX");
+            return 4;
+        }
+
+        1
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::error::SpellError;
+use crate::spell_checker::{LoadOptions, SpellChecker};
+
+/// Keeps a [`SpellChecker`] in sync with its dictionary file for long-running
+/// processes. A background thread polls the file's modification time and
+/// size; when either changes the dictionary is rebuilt and swapped in. A
+/// reload that fails (e.g. the file is momentarily empty mid-write) keeps
+/// the previous dictionary and is reported to the `on_error` callback.
+pub struct DictionaryWatcher {
+    current: Arc<RwLock<Arc<SpellChecker>>>,
+    reloads: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+type Configure = dyn Fn(SpellChecker) -> SpellChecker + Send + Sync;
+
+fn fingerprint(path: &PathBuf) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl DictionaryWatcher {
+    /// Loads the dictionary at `path` and starts watching it every
+    /// `interval`. `configure` is applied to every freshly loaded checker so
+    /// settings such as `with_max_distance` survive a reload. `on_error` is
+    /// called from the watching thread with the error of each failed reload.
+    pub fn watch<P, F, E>(
+        path: P,
+        options: LoadOptions,
+        interval: Duration,
+        configure: F,
+        on_error: E,
+    ) -> Result<Self, SpellError>
+    where
+        P: Into<PathBuf>,
+        F: Fn(SpellChecker) -> SpellChecker + Send + Sync + 'static,
+        E: Fn(&SpellError) + Send + 'static,
+    {
+        let path = path.into();
+        let configure: Arc<Configure> = Arc::new(configure);
+        let mut seen = fingerprint(&path);
+        let checker = configure(SpellChecker::load(&path, &options)?);
+
+        let current = Arc::new(RwLock::new(Arc::new(checker)));
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let current = Arc::clone(&current);
+            let reloads = Arc::clone(&reloads);
+            let stop = Arc::clone(&stop);
+
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+
+                    let latest = fingerprint(&path);
+                    if latest == seen {
+                        continue;
+                    }
+                    seen = latest;
+
+                    match SpellChecker::load(&path, &options) {
+                        Ok(checker) => {
+                            let checker = Arc::new(configure(checker));
+                            *current.write().expect("dictionary lock poisoned") = checker;
+                            reloads.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(e) => on_error(&e),
+                    }
+                }
+            })
+        };
+
+        Ok(DictionaryWatcher {
+            current,
+            reloads,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// The checker for the current dictionary. Callers keep using the
+    /// returned snapshot even if a reload swaps in a newer one meanwhile.
+    pub fn checker(&self) -> Arc<SpellChecker> {
+        Arc::clone(&self.current.read().expect("dictionary lock poisoned"))
+    }
+
+    /// Number of successful reloads since watching started.
+    pub fn reloads(&self) -> usize {
+        self.reloads.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for DictionaryWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
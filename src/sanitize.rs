@@ -0,0 +1,30 @@
+/// What to do with control characters (NUL, form feed, ...) found inside
+/// dictionary entries or input words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlCharPolicy {
+    /// Remove the control characters and keep the rest of the text.
+    #[default]
+    Strip,
+    /// Drop the dictionary entry, or fail the input line.
+    Reject,
+    /// Pass them through untouched.
+    Keep,
+}
+
+impl ControlCharPolicy {
+    /// Applies the policy to one piece of text. Returns `None` if it must be
+    /// rejected, and whether anything was changed or rejected.
+    pub fn apply(&self, text: &str) -> (Option<String>, bool) {
+        if *self == ControlCharPolicy::Keep || !text.chars().any(char::is_control) {
+            return (Some(text.to_string()), false);
+        }
+
+        match self {
+            ControlCharPolicy::Strip => (
+                Some(text.chars().filter(|c| !c.is_control()).collect()),
+                true,
+            ),
+            _ => (None, true),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+/// Coarse Unicode script classification, enough to keep corrections within
+/// the writing system of the input word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+    /// Digits, punctuation and anything not covered above.
+    Common,
+}
+
+impl Script {
+    pub fn of_char(c: char) -> Script {
+        match c {
+            'A'..='Z' | 'a'..='z' => Script::Latin,
+            '\u{00c0}'..='\u{024f}' | '\u{1e00}'..='\u{1eff}' if c.is_alphabetic() => Script::Latin,
+            '\u{0370}'..='\u{03ff}' | '\u{1f00}'..='\u{1fff}' => Script::Greek,
+            '\u{0400}'..='\u{052f}' => Script::Cyrillic,
+            '\u{0530}'..='\u{058f}' => Script::Armenian,
+            '\u{0590}'..='\u{05ff}' => Script::Hebrew,
+            '\u{0600}'..='\u{06ff}' | '\u{0750}'..='\u{077f}' => Script::Arabic,
+            '\u{0900}'..='\u{097f}' => Script::Devanagari,
+            '\u{0e00}'..='\u{0e7f}' => Script::Thai,
+            '\u{1100}'..='\u{11ff}' | '\u{ac00}'..='\u{d7af}' => Script::Hangul,
+            '\u{3040}'..='\u{30ff}' => Script::Kana,
+            '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => Script::Han,
+            _ => Script::Common,
+        }
+    }
+
+    /// The most frequent non-common script in `word`, or `Common` if the word
+    /// has no script-specific characters. Ties go to the script seen first.
+    pub fn dominant(word: &str) -> Script {
+        let mut counts: Vec<(Script, usize)> = Vec::new();
+
+        for script in word.chars().map(Script::of_char) {
+            if script == Script::Common {
+                continue;
+            }
+            match counts.iter_mut().find(|(s, _)| *s == script) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((script, 1)),
+            }
+        }
+
+        counts
+            .into_iter()
+            .fold(
+                None,
+                |best: Option<(Script, usize)>, (script, count)| match best {
+                    Some((_, best_count)) if best_count >= count => best,
+                    _ => Some((script, count)),
+                },
+            )
+            .map_or(Script::Common, |(script, _)| script)
+    }
+}
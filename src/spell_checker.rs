@@ -0,0 +1,2090 @@
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use strsim::levenshtein;
+
+use crate::audit::{Decision, Strategy};
+use crate::candidates::{CandidateSource, LinearScan};
+use crate::compose::compose;
+use crate::dictionary::{Dictionary, fold};
+use crate::distance::{EditCosts, EditOp, edit_script, levenshtein_bounded, weighted_levenshtein};
+use crate::error::SpellError;
+use crate::memory::{HeapSize, MemoryReport};
+use crate::phonetic::Phonetic;
+use crate::rng::{DEFAULT_SEED, SplitMix64};
+use crate::sanitize::ControlCharPolicy;
+use crate::script::Script;
+use crate::stem::stem;
+use crate::suggestion::Suggestion;
+use crate::trace;
+use crate::typography::restore_typography;
+use crate::word_list::{Token, WordList};
+
+/// Default per-edit penalty used by [`SpellChecker::score`].
+pub const DEFAULT_PENALTY_BASE: f64 = 0.05;
+
+/// Default cap on the candidates measured for a single word, see
+/// [`SpellChecker::with_max_candidates`].
+pub const DEFAULT_MAX_CANDIDATES: usize = 50_000;
+
+/// Counters describing how much distance work the checker has done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComparisonStats {
+    /// Words that were not in the dictionary and required a candidate search.
+    pub searches: usize,
+    /// Distance computations performed across all searches.
+    pub comparisons: usize,
+}
+
+impl ComparisonStats {
+    pub fn average(&self) -> f64 {
+        if self.searches == 0 {
+            0.0
+        } else {
+            self.comparisons as f64 / self.searches as f64
+        }
+    }
+}
+
+/// The word tokens of one entry that correction would change.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineDiff {
+    pub id: String,
+    /// `(token_index, old, new)`, where `token_index` points into the
+    /// entry's original `tokens`.
+    pub changes: Vec<(usize, String, String)>,
+}
+
+impl LineDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// The outcome of [`SpellChecker::correct_all_tracked`].
+#[derive(Debug, Clone)]
+pub struct CorrectionRun {
+    /// One result per input list, in input order: the corrected list and its
+    /// `(original, replacement)` pairs. Lists not reached before cancellation
+    /// are passed through as written with no changes.
+    pub results: Vec<(WordList, Vec<(String, String)>)>,
+    /// Whether each list, by index, was reached before cancellation.
+    pub finished: Vec<bool>,
+}
+
+impl CorrectionRun {
+    /// Number of lists reached before cancellation.
+    pub fn completed(&self) -> usize {
+        self.finished.iter().filter(|&&finished| finished).count()
+    }
+}
+
+/// A word for which the configured candidate source picked a different
+/// correction than a scan of the whole dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub word: String,
+    pub fast: String,
+    pub fast_distance: usize,
+    pub reference: String,
+    pub reference_distance: usize,
+}
+
+/// What correcting a line did to one of its tokens, as reported by
+/// [`SpellChecker::correct_word_list_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenOutcome {
+    /// A known or skipped word, left as written.
+    Unchanged,
+    /// A word replaced by `to`. Segmented words report their parts joined
+    /// by spaces.
+    Corrected {
+        from: String,
+        to: String,
+        distance: usize,
+    },
+    /// A word missing from the dictionary and left as written, because no
+    /// candidate was close enough or the line's correction limit was reached.
+    NoSuggestion,
+    /// A word removed by [`SpellChecker::with_drop_unknown`].
+    Dropped,
+    /// A separator or delimiter, copied unless it went with a dropped word.
+    Separator,
+}
+
+/// How to choose among candidates at the same edit distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The alphabetically first candidate.
+    Alphabetical,
+    /// The most frequent candidate per the frequency list, then
+    /// alphabetical; without a list this is alphabetical.
+    Frequency,
+    /// The candidate with the fewest characters, then alphabetical. Suits
+    /// typos made by adding stray letters.
+    ShortestFirst,
+}
+
+/// Settings applied while loading a dictionary.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Handling of control characters inside dictionary entries.
+    pub control_chars: ControlCharPolicy,
+    /// Characters that split input words. An entry containing one can never
+    /// match a token, since no token contains it.
+    pub separators: Vec<char>,
+    /// Drop such entries instead of only counting them.
+    pub drop_unmatchable: bool,
+    /// Extra attempts after a read fails with an error that may clear up,
+    /// such as the file being locked or mid-write.
+    pub retries: usize,
+    /// Wait before the first retry; each later retry waits one step longer.
+    pub retry_backoff: Duration,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            control_chars: ControlCharPolicy::default(),
+            separators: vec![' ', '/'],
+            drop_unmatchable: false,
+            retries: 0,
+            retry_backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+pub struct SpellChecker {
+    dictionary: Arc<Dictionary>,
+    frequencies: HashMap<String, u64>,
+    penalty_base: f64,
+    max_distance: Option<usize>,
+    max_distance_ratio: Option<f64>,
+    drop_unknown: bool,
+    max_corrections_per_line: Option<usize>,
+    skip_if_clean: Option<f64>,
+    max_segments: Option<usize>,
+    edit_costs: Option<EditCosts>,
+    same_script: bool,
+    exhaustive: bool,
+    /// `None` ranks by frequency when a frequency list is loaded and
+    /// alphabetically otherwise.
+    tie_break: Option<TieBreak>,
+    annotate_distance: bool,
+    /// Only check membership; unknown words are kept as written.
+    check_only: bool,
+    /// Widen or narrow the distance limit by one depending on how many of
+    /// a word's neighbours on its line are known.
+    context_distance: bool,
+    source: Box<dyn CandidateSource>,
+    /// Stem -> indices of dictionary entries with that stem. Empty unless
+    /// stemming is enabled.
+    stems: HashMap<String, Vec<usize>>,
+    phonetic: Option<Phonetic>,
+    /// Phonetic key -> indices of dictionary entries with that key. Empty
+    /// unless a phonetic algorithm is selected.
+    phonetic_keys: HashMap<String, Vec<usize>>,
+    seed: u64,
+    /// Input word -> candidates it must never be corrected to.
+    forbidden: HashMap<String, HashSet<String>>,
+    /// Input word -> correction applied unconditionally.
+    forced: HashMap<String, String>,
+    /// Words left as written though absent from `dictionary`, and never
+    /// suggested for other words.
+    accepted: Option<Arc<Dictionary>>,
+    /// Confirmed corrections from earlier sessions; `forced` wins over these.
+    learned: HashMap<String, String>,
+    /// `learned` then `forced`, keyed by [`SpellChecker::lookup_key`] so
+    /// every lookup normalizes the word the same way.
+    substitutions: HashMap<String, String>,
+    ignore_case: bool,
+    normalize_case: bool,
+    /// Leave numeric tokens such as "2024" or "3.14" untouched.
+    ignore_numeric: bool,
+    /// Leave all-uppercase tokens such as "HTTP" untouched.
+    skip_acronyms: bool,
+    /// Try shortening runs of 3+ identical letters ("soooo") before searching.
+    collapse_repeats: bool,
+    /// Leave tokens whose share of non-letter characters exceeds this untouched.
+    max_symbol_ratio: Option<f64>,
+    /// Most candidates measured for one word; the most promising are kept.
+    max_candidates: usize,
+    /// Normalized word -> correction, when caching is enabled.
+    cache: Option<Mutex<HashMap<String, (String, Strategy)>>>,
+    cache_hits: AtomicUsize,
+    searches: AtomicUsize,
+    comparisons: AtomicUsize,
+}
+
+impl SpellChecker {
+    #[cfg(feature = "fs")]
+    pub fn new<P: AsRef<Path>>(dict_path: P) -> Result<Self, SpellError> {
+        Self::load(dict_path, &LoadOptions::default())
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn load<P: AsRef<Path>>(dict_path: P, options: &LoadOptions) -> Result<Self, SpellError> {
+        Ok(Self::from_dictionary(Dictionary::load(dict_path, options)?))
+    }
+
+    /// Builds a checker from dictionary text with one word per line, as read
+    /// by [`SpellChecker::load`]. Needs no filesystem access.
+    pub fn from_content(dict_content: &str, options: &LoadOptions) -> Result<Self, SpellError> {
+        Ok(Self::from_dictionary(Dictionary::from_content(
+            dict_content,
+            options,
+        )?))
+    }
+
+    /// Builds a checker from an in-memory word collection, normalized as in
+    /// [`Dictionary::from_words`].
+    pub fn from_words<I: IntoIterator<Item = String>>(words: I) -> Result<Self, SpellError> {
+        Ok(Self::from_dictionary(Dictionary::from_words(words)?))
+    }
+
+    /// Number of dictionary entries that contained control characters and
+    /// were stripped or dropped while loading.
+    pub fn sanitized_entries(&self) -> usize {
+        self.dictionary.sanitized_entries()
+    }
+
+    /// Number of dictionary entries containing a separator character, which
+    /// no input token can match. Counted whether or not they were dropped.
+    pub fn unmatchable_entries(&self) -> usize {
+        self.dictionary.unmatchable_entries()
+    }
+
+    /// A checker with default settings over `dictionary`. Pass an
+    /// `Arc<Dictionary>` clone to share one loaded dictionary between
+    /// checkers; indexes that depend on settings, such as stems, are built
+    /// per checker.
+    pub fn from_dictionary(dictionary: impl Into<Arc<Dictionary>>) -> Self {
+        SpellChecker {
+            dictionary: dictionary.into(),
+            frequencies: HashMap::new(),
+            penalty_base: DEFAULT_PENALTY_BASE,
+            max_distance: None,
+            max_distance_ratio: None,
+            drop_unknown: false,
+            max_corrections_per_line: None,
+            skip_if_clean: None,
+            max_segments: None,
+            edit_costs: None,
+            same_script: false,
+            exhaustive: false,
+            tie_break: None,
+            annotate_distance: false,
+            check_only: false,
+            context_distance: false,
+            source: Box::new(LinearScan),
+            stems: HashMap::new(),
+            phonetic: None,
+            phonetic_keys: HashMap::new(),
+            seed: DEFAULT_SEED,
+            forbidden: HashMap::new(),
+            forced: HashMap::new(),
+            learned: HashMap::new(),
+            substitutions: HashMap::new(),
+            accepted: None,
+            ignore_case: false,
+            normalize_case: false,
+            ignore_numeric: true,
+            skip_acronyms: false,
+            collapse_repeats: false,
+            max_symbol_ratio: None,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            cache: None,
+            cache_hits: AtomicUsize::new(0),
+            searches: AtomicUsize::new(0),
+            comparisons: AtomicUsize::new(0),
+        }
+    }
+
+    /// Loads a frequency list with one `word count` pair per line.
+    #[cfg(feature = "fs")]
+    pub fn load_frequencies<P: AsRef<Path>>(path: P) -> Result<HashMap<String, u64>, SpellError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| SpellError::Io(format!("Failed to load frequencies: {}", e)))?;
+        Self::parse_frequencies(&content)
+    }
+
+    /// Parses the `word count` lines of a frequency list.
+    pub fn parse_frequencies(content: &str) -> Result<HashMap<String, u64>, SpellError> {
+        let mut frequencies = HashMap::new();
+
+        for (line_num, line) in content.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let entry = match (parts.next(), parts.next(), parts.next()) {
+                (Some(word), Some(count), None) => count.parse::<u64>().ok().map(|c| (word, c)),
+                _ => None,
+            };
+
+            match entry {
+                Some((word, count)) => {
+                    *frequencies.entry(word.to_string()).or_insert(0) += count;
+                }
+                None => {
+                    return Err(SpellError::InvalidLine {
+                        line: line_num,
+                        reason: format!("has invalid frequency entry: '{}'", line),
+                    });
+                }
+            }
+        }
+
+        Ok(frequencies)
+    }
+
+    #[cfg(feature = "fs")]
+    fn read_list<P: AsRef<Path>>(path: P, what: &str) -> Result<String, SpellError> {
+        fs::read_to_string(path)
+            .map_err(|e| SpellError::Io(format!("Failed to load {}: {}", what, e)))
+    }
+
+    /// Parses whitespace-separated `from to` pairs, one per line.
+    fn parse_pairs(content: &str, what: &str) -> Result<Vec<(String, String)>, SpellError> {
+        let mut pairs = Vec::new();
+
+        for (line_num, line) in content.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(from), Some(to), None) => pairs.push((from.to_string(), to.to_string())),
+                _ => {
+                    return Err(SpellError::InvalidLine {
+                        line: line_num,
+                        reason: format!("has invalid {} entry: '{}'", what, line),
+                    });
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    /// Loads `from to` substitutions that must never be made.
+    #[cfg(feature = "fs")]
+    pub fn load_forbidden<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<HashSet<(String, String)>, SpellError> {
+        Self::parse_forbidden(&Self::read_list(path, "forbidden substitutions")?)
+    }
+
+    pub fn parse_forbidden(content: &str) -> Result<HashSet<(String, String)>, SpellError> {
+        Ok(Self::parse_pairs(content, "forbidden substitutions")?
+            .into_iter()
+            .collect())
+    }
+
+    /// Never correct `from` to `to` for any pair in `forbidden`; the next best
+    /// candidate is used instead, or the word is kept if none remain.
+    pub fn with_forbidden(mut self, forbidden: HashSet<(String, String)>) -> Self {
+        self.forbidden.clear();
+        for (from, to) in forbidden {
+            self.forbidden.entry(from).or_default().insert(to);
+        }
+        self
+    }
+
+    fn is_forbidden(&self, word: &str, candidate: &str) -> bool {
+        self.forbidden
+            .get(word)
+            .is_some_and(|targets| targets.contains(candidate))
+    }
+
+    /// Loads `from to` corrections that are always applied.
+    #[cfg(feature = "fs")]
+    pub fn load_forced<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>, SpellError> {
+        Self::parse_forced(&Self::read_list(path, "forced corrections")?)
+    }
+
+    pub fn parse_forced(content: &str) -> Result<HashMap<String, String>, SpellError> {
+        Ok(Self::parse_pairs(content, "forced corrections")?
+            .into_iter()
+            .collect())
+    }
+
+    /// Always correct a key of `forced` to its value, before any dictionary
+    /// lookup or distance work. Keys match up to case when case is ignored.
+    pub fn with_forced(mut self, forced: HashMap<String, String>) -> Self {
+        self.forced = forced;
+        self.index_substitutions();
+        self
+    }
+
+    /// Corrections confirmed in earlier sessions, typically from a
+    /// `LearnedCorrections` store. They apply
+    /// like forced corrections, and a forced correction for the same word
+    /// takes precedence.
+    pub fn with_learned(mut self, learned: HashMap<String, String>) -> Self {
+        self.learned = learned;
+        self.index_substitutions();
+        self
+    }
+
+    /// Rebuilds `substitutions` after the maps or the key normalization changed.
+    fn index_substitutions(&mut self) {
+        let substitutions = self
+            .learned
+            .iter()
+            .chain(&self.forced)
+            .map(|(from, to)| (self.lookup_key(from), to.clone()))
+            .collect();
+        self.substitutions = substitutions;
+    }
+
+    /// A secondary dictionary of words to accept but not suggest, such as
+    /// archaic spellings or regional variants. A word missing from the main
+    /// dictionary but found here is left unchanged; other words are never
+    /// corrected to one of these. Forced corrections still apply.
+    pub fn with_accepted(mut self, accepted: impl Into<Arc<Dictionary>>) -> Self {
+        self.accepted = Some(accepted.into());
+        self
+    }
+
+    /// Whether `word` is in the secondary dictionary and not the main one.
+    fn accepts(&self, word: &str) -> bool {
+        self.accepted.as_ref().is_some_and(|accepted| {
+            accepted.contains(word) || (self.ignore_case && accepted.folded(word).is_some())
+        }) && !self.contains_word(word)
+    }
+
+    /// The forced or learned correction for `word`, if any, matched on its
+    /// lookup key: up to case when case is ignored.
+    fn forced_correction(&self, word: &str) -> Option<&String> {
+        if self.substitutions.is_empty() {
+            return None;
+        }
+        self.substitutions.get(&self.lookup_key(word))
+    }
+
+    pub fn with_frequencies(mut self, frequencies: HashMap<String, u64>) -> Self {
+        self.frequencies = frequencies;
+        self
+    }
+
+    pub fn with_penalty_base(mut self, penalty_base: f64) -> Self {
+        self.penalty_base = penalty_base;
+        self
+    }
+
+    /// Words whose nearest candidate is farther than `max_distance` are left unchanged.
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    /// Scales the threshold with the word: at most `floor(len * ratio)` edits,
+    /// with `len` in characters. With ratio 0.3, "recieve" may take 2 edits
+    /// and "to" none. When `max_distance` is also set, the smaller limit applies.
+    pub fn with_max_distance_ratio(mut self, ratio: f64) -> Self {
+        self.max_distance_ratio = Some(ratio);
+        self
+    }
+
+    /// Remove word tokens that could not be corrected to a dictionary word
+    /// from the output of [`SpellChecker::correct_word_list`].
+    pub fn with_drop_unknown(mut self, drop_unknown: bool) -> Self {
+        self.drop_unknown = drop_unknown;
+        self
+    }
+
+    /// Pass entries through unchanged when at least `ratio` of their words
+    /// are already known (see [`SpellChecker::known_ratio`]), so mostly clean
+    /// lines cost only membership lookups. The few misspellings in such a
+    /// line are left uncorrected.
+    pub fn with_skip_if_clean(mut self, ratio: f64) -> Self {
+        self.skip_if_clean = Some(ratio);
+        self
+    }
+
+    /// Correct at most `limit` words per entry: those whose correction is
+    /// closest (ties go to the earlier token). The other words are left as
+    /// they are, without segmentation or dropping.
+    pub fn with_max_corrections_per_line(mut self, limit: usize) -> Self {
+        self.max_corrections_per_line = Some(limit);
+        self
+    }
+
+    /// The `limit` most confident corrections in `word_list`, keyed by token index.
+    fn most_confident_corrections(
+        &self,
+        word_list: &WordList,
+        limit: usize,
+    ) -> HashMap<usize, (String, Strategy)> {
+        let mut corrections: Vec<(usize, usize, (String, Strategy))> = word_list
+            .tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(index, token)| match token {
+                Token::Word(word) => {
+                    let mut corrected = String::new();
+                    let strategy = self.correct_word_with_strategy(word, &mut corrected);
+                    (corrected != *word).then(|| {
+                        (
+                            self.edit_distance(word, &corrected),
+                            index,
+                            (corrected, strategy),
+                        )
+                    })
+                }
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+            .collect();
+
+        corrections.sort_by_key(|&(distance, index, _)| (distance, index));
+        corrections
+            .into_iter()
+            .take(limit)
+            .map(|(_, index, correction)| (index, correction))
+            .collect()
+    }
+
+    /// Try splitting run-together words ("thequick") into at most
+    /// `max_segments` dictionary words when no one-edit correction exists.
+    pub fn with_segmentation(mut self, max_segments: usize) -> Self {
+        self.max_segments = Some(max_segments);
+        self
+    }
+
+    /// Use [`weighted_levenshtein`] with `costs` instead of plain Levenshtein.
+    pub fn with_edit_costs(mut self, costs: EditCosts) -> Self {
+        self.edit_costs = Some(costs);
+        self
+    }
+
+    /// Replace the default [`LinearScan`] with another way of picking the
+    /// entries to compare against.
+    pub fn with_candidate_source(mut self, mut source: Box<dyn CandidateSource>) -> Self {
+        source.build(self.dictionary.words());
+        self.source = source;
+        self
+    }
+
+    /// Append the edit distance to every changed word in corrected entries,
+    /// as in `the[2]`. For inspection only: the brackets become part of the
+    /// word, so annotated output is no longer valid input.
+    pub fn with_annotate_distance(mut self, annotate_distance: bool) -> Self {
+        self.annotate_distance = annotate_distance;
+        self
+    }
+
+    /// How to choose among equally distant candidates. By default a loaded
+    /// frequency list decides ([`TieBreak::Frequency`]), and otherwise the
+    /// alphabetically first candidate wins.
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = Some(tie_break);
+        self
+    }
+
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break.unwrap_or(if self.frequencies.is_empty() {
+            TieBreak::Alphabetical
+        } else {
+            TieBreak::Frequency
+        })
+    }
+
+    /// Check membership only and leave unknown words unchanged. Useful for
+    /// dictionaries too small to pick sensible corrections from, where every
+    /// word would otherwise be "corrected" to one of a handful of entries.
+    pub fn with_check_only(mut self, check_only: bool) -> Self {
+        self.check_only = check_only;
+        self
+    }
+
+    /// Let a word's line decide how far it may be corrected. If every other
+    /// word on the line is known, the word is likely an isolated typo and
+    /// may be corrected one edit further than the distance limit allows. If
+    /// fewer than half of them are known, the line is probably not text the
+    /// dictionary covers, and the limit shrinks by one. Lines in between, and
+    /// words with no neighbours, keep the usual limit. Has no effect without
+    /// a distance limit, and only applies through
+    /// [`SpellChecker::correct_word_list`] and the functions built on it.
+    pub fn with_context_distance(mut self, context_distance: bool) -> Self {
+        self.context_distance = context_distance;
+        self
+    }
+
+    /// Compare `word` against every dictionary entry instead of only those
+    /// the configured [`CandidateSource`] and the
+    /// [`SpellChecker::with_max_candidates`] cap let through, so the result
+    /// is always the true nearest entry. Ties are still settled by the
+    /// configured [`TieBreak`]. Slower on large dictionaries, since nothing
+    /// is pruned before measuring.
+    pub fn with_exhaustive(mut self, exhaustive: bool) -> Self {
+        self.exhaustive = exhaustive;
+        self
+    }
+
+    /// Only consider candidates written in the same script as the input word,
+    /// so e.g. a Latin typo is never corrected to a Cyrillic entry. Words
+    /// without script-specific characters are compared against everything.
+    pub fn with_same_script(mut self, same_script: bool) -> Self {
+        self.same_script = same_script;
+        self
+    }
+
+    /// Match words against dictionary entries sharing their stem, so an
+    /// inflected or misspelled inflection ("runing") corrects to a dictionary
+    /// surface form with the same stem ("running") before any distance search.
+    /// This changes semantics: an inflection absent from the dictionary is
+    /// rewritten to the closest listed form of the same stem.
+    pub fn with_stemming(mut self, stemming: bool) -> Self {
+        let mut span = trace::span("build_stem_index");
+        self.stems.clear();
+        if stemming {
+            for (index, word) in self.dictionary.words.iter().enumerate() {
+                self.stems.entry(stem(word)).or_default().push(index);
+            }
+        }
+        span.record("stems", self.stems.len());
+        self
+    }
+
+    /// Fall back to words that sound alike when the closest spelling does
+    /// not: if the best edit-distance candidate shares no phonetic key with
+    /// the input, the nearest entry that does is used instead ("fone" ->
+    /// "phone" rather than "one"). Phonetic matches are not limited by
+    /// `max_distance`. Keys for the whole dictionary are computed here.
+    pub fn with_phonetic(mut self, phonetic: Option<Phonetic>) -> Self {
+        let mut span = trace::span("build_phonetic_index");
+        self.phonetic = phonetic;
+        self.phonetic_keys.clear();
+        if let Some(phonetic) = phonetic {
+            for (index, word) in self.dictionary.words.iter().enumerate() {
+                for key in phonetic.keys(word) {
+                    self.phonetic_keys.entry(key).or_default().push(index);
+                }
+            }
+        }
+        span.record("keys", self.phonetic_keys.len());
+        self
+    }
+
+    /// Seed for any randomized decision. Output is deterministic for a given
+    /// seed; hash maps are only ever used for lookups, never iterated to rank.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// A fresh generator seeded from the checker's seed.
+    pub fn rng(&self) -> SplitMix64 {
+        SplitMix64::new(self.seed)
+    }
+
+    /// Match words against the dictionary case-insensitively. Words are
+    /// corrected in lowercase and the result is re-cased to follow the
+    /// original token ("Teh" -> "The", "TEH" -> "THE").
+    ///
+    /// An exact, case-sensitive match always takes priority, so a dictionary
+    /// can hold both "Polish" and "polish" and keep each as written; only a
+    /// word matching no entry exactly falls back to the case-folded index.
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self.index_substitutions();
+        self
+    }
+
+    /// With case-insensitive matching, rewrite words that differ from a
+    /// dictionary entry only by case to the dictionary's spelling ("paris" ->
+    /// "Paris"). Off by default: such words are already correct and are kept
+    /// as written.
+    pub fn with_normalize_case(mut self, normalize_case: bool) -> Self {
+        self.normalize_case = normalize_case;
+        self
+    }
+
+    /// Whether numeric word tokens are left unchanged (the default) or
+    /// corrected like any other word. A numeric token in the words part is
+    /// never an ID; only the four-digit line prefix is.
+    pub fn with_ignore_numeric(mut self, ignore_numeric: bool) -> Self {
+        self.ignore_numeric = ignore_numeric;
+        self
+    }
+
+    /// Leave all-uppercase tokens of more than one letter, which are usually
+    /// acronyms ("HTTP", "NASA"), unchanged instead of correcting them.
+    pub fn with_skip_acronyms(mut self, skip_acronyms: bool) -> Self {
+        self.skip_acronyms = skip_acronyms;
+        self
+    }
+
+    /// Before searching, shorten every run of three or more identical
+    /// letters to one or two ("soooo" -> "so" or "soo") and take the first
+    /// form that is a dictionary entry, preferring the one that keeps more
+    /// letters. Runs of two are never touched, so "cool" and "committee"
+    /// stay valid.
+    pub fn with_collapse_repeats(mut self, collapse_repeats: bool) -> Self {
+        self.collapse_repeats = collapse_repeats;
+        self
+    }
+
+    /// Leave tokens unchanged when more than `ratio` of their characters are
+    /// not letters, such as version strings ("v2.1") or identifiers
+    /// ("x_86"). Ordinary words, apostrophes and all, stay well below 0.5.
+    pub fn with_max_symbol_ratio(mut self, ratio: f64) -> Self {
+        self.max_symbol_ratio = Some(ratio);
+        self
+    }
+
+    /// Measure at most `max` candidates per word. Short inputs sit within a
+    /// couple of edits of a huge share of a large dictionary; past the cap,
+    /// candidates sharing the word's first letter, then those closest to it
+    /// in length, are kept and the rest are never compared. Defaults to
+    /// [`DEFAULT_MAX_CANDIDATES`].
+    pub fn with_max_candidates(mut self, max: usize) -> Self {
+        self.max_candidates = max;
+        self
+    }
+
+    /// Remember corrections so repeated words are only searched once. The key
+    /// is the normalized form: accents written as combining marks are
+    /// composed, and with case-insensitive matching "Teh", "teh" and "TEH"
+    /// share one entry.
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = cache.then(|| Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Number of corrections served from the cache.
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// A maximum distance suited to this dictionary: a third of the mean
+    /// entry length in characters, rounded, clamped to `1..=3`. Longer words
+    /// tolerate more edits before a different word becomes a likelier match,
+    /// while beyond three edits almost any short entry is "close".
+    pub fn suggested_max_distance(&self) -> usize {
+        let total: usize = self
+            .dictionary
+            .words()
+            .iter()
+            .map(|word| word.chars().count())
+            .sum();
+        let mean = total as f64 / self.dictionary.len() as f64;
+        ((mean / 3.0).round() as usize).clamp(1, 3)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dictionary.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dictionary.is_empty()
+    }
+
+    /// Whether `word` is in the dictionary. The exact spelling is looked up
+    /// first; with [`SpellChecker::with_ignore_case`] a word that matches an
+    /// entry only up to case ("paris" for "Paris") counts as well.
+    pub fn contains_word(&self, word: &str) -> bool {
+        self.contains_exact(word) || (self.ignore_case && self.dictionary.folded(word).is_some())
+    }
+
+    fn contains_exact(&self, word: &str) -> bool {
+        self.dictionary.contains(word)
+    }
+
+    /// All dictionary entries starting with `prefix`, in sorted order. An empty
+    /// prefix returns the whole dictionary.
+    pub fn words_with_prefix(&self, prefix: &str) -> &[String] {
+        self.dictionary.words_with_prefix(prefix)
+    }
+
+    /// The dictionary this checker corrects against.
+    pub fn dictionary(&self) -> &Arc<Dictionary> {
+        &self.dictionary
+    }
+
+    /// Returns `true` if stemming is enabled and some dictionary entry shares
+    /// the stem of `word`.
+    pub fn contains_stem(&self, word: &str) -> bool {
+        !self.stems.is_empty() && self.stems.contains_key(&stem(word))
+    }
+
+    /// The dictionary entry sharing `word`'s stem that is closest to `word`,
+    /// ties broken alphabetically.
+    fn stem_match(&self, word: &str) -> Option<&str> {
+        self.stems
+            .get(&stem(word))?
+            .iter()
+            .map(|&index| self.dictionary.words[index].as_str())
+            .filter(|candidate| !self.is_forbidden(word, candidate))
+            .map(|candidate| (self.distance(word, candidate), candidate))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// The nearest entry sounding like `word`, if `best` (the closest
+    /// spelling) does not. Ties are broken alphabetically.
+    fn phonetic_match(&self, word: &str, best: &str) -> Option<&str> {
+        let phonetic = self.phonetic?;
+        let keys = phonetic.keys(word);
+        if phonetic.keys(best).iter().any(|key| keys.contains(key)) {
+            return None;
+        }
+
+        keys.iter()
+            .filter_map(|key| self.phonetic_keys.get(key))
+            .flatten()
+            .map(|&index| self.dictionary.words[index].as_str())
+            .filter(|candidate| !self.is_forbidden(word, candidate))
+            .map(|candidate| (self.distance(word, candidate), candidate))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// Norvig-style score approximating `P(candidate) * P(word | candidate)`:
+    ///
+    /// `score = (freq + 1) * penalty_base ^ distance`
+    ///
+    /// `freq` is the candidate's count in the frequency list (add-one smoothed so
+    /// unlisted words still score), and `penalty_base` in `(0, 1)` is the factor
+    /// each additional edit multiplies the likelihood by. Without a frequency
+    /// list every candidate has the same prior and ranking reduces to distance.
+    pub fn score(&self, candidate: &str, distance: usize) -> f64 {
+        let frequency = self.frequencies.get(candidate).copied().unwrap_or(0) as f64 + 1.0;
+        frequency * self.penalty_base.powi(distance as i32)
+    }
+
+    /// Returns up to `limit` candidates, highest score first. Ties on score
+    /// fall back to the smaller distance, then alphabetical order. Sorting
+    /// the result puts it in [`Suggestion`]'s distance-first order instead.
+    pub fn correct_word_candidates(&self, word: &str, limit: usize) -> Vec<Suggestion> {
+        let mut candidates: Vec<(&String, usize, f64)> = self
+            .candidate_pool(word)
+            .map(|candidate| (candidate, self.distance(word, candidate)))
+            .filter(|&(_, distance)| self.within_max_distance(word, distance))
+            .map(|(candidate, distance)| (candidate, distance, self.score(candidate, distance)))
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            b.2.total_cmp(&a.2)
+                .then(a.1.cmp(&b.1))
+                .then_with(|| a.0.cmp(b.0))
+        });
+
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(candidate, distance, score)| Suggestion::new(candidate.clone(), distance, score))
+            .collect()
+    }
+
+    /// Estimated heap memory of the dictionary, its indexes and the cache.
+    /// See [`MemoryReport`] for what the estimates cover.
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport {
+            dictionary: self.dictionary.words.heap_size(),
+            scripts: self.dictionary.scripts.capacity() * std::mem::size_of::<Script>(),
+            folded: self.dictionary.folded.heap_size(),
+            frequencies: self.frequencies.heap_size(),
+            stems: self.stems.heap_size(),
+            phonetic_keys: self.phonetic_keys.heap_size(),
+            substitutions: self.forced.heap_size()
+                + self.learned.heap_size()
+                + self.substitutions.heap_size()
+                + self.forbidden.heap_size(),
+            candidate_source: self.source.heap_bytes(),
+            cache: self
+                .cache
+                .as_ref()
+                .map_or(0, |cache| cache.lock().unwrap().heap_size()),
+        }
+    }
+
+    pub fn comparison_stats(&self) -> ComparisonStats {
+        ComparisonStats {
+            searches: self.searches.load(Ordering::Relaxed),
+            comparisons: self.comparisons.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset_comparison_stats(&self) {
+        self.searches.store(0, Ordering::Relaxed);
+        self.comparisons.store(0, Ordering::Relaxed);
+    }
+
+    fn distance(&self, word: &str, candidate: &str) -> usize {
+        self.comparisons.fetch_add(1, Ordering::Relaxed);
+        self.edit_distance(word, candidate)
+    }
+
+    /// Like [`SpellChecker::distance`], but `None` as soon as the distance is
+    /// known to exceed `max`. Plain Levenshtein stops early; weighted costs
+    /// are computed in full.
+    fn distance_within(&self, word: &str, candidate: &str, max: usize) -> Option<usize> {
+        self.comparisons.fetch_add(1, Ordering::Relaxed);
+        match &self.edit_costs {
+            Some(costs) => Some(weighted_levenshtein(word, candidate, costs)).filter(|&d| d <= max),
+            None => levenshtein_bounded(word, candidate, max),
+        }
+    }
+
+    /// Distance between two words under the configured edit costs. Unlike
+    /// the searches, this is not counted in [`SpellChecker::comparison_stats`].
+    pub fn edit_distance(&self, word: &str, candidate: &str) -> usize {
+        match &self.edit_costs {
+            Some(costs) => weighted_levenshtein(word, candidate, costs),
+            None => levenshtein(word, candidate),
+        }
+    }
+
+    /// Like [`SpellChecker::correct_word_candidates`], with each candidate paired
+    /// with the edits that turn `word` into it.
+    pub fn explain_candidates(&self, word: &str, limit: usize) -> Vec<(String, Vec<EditOp>)> {
+        self.correct_word_candidates(word, limit)
+            .into_iter()
+            .map(|suggestion| {
+                let script = edit_script(word, suggestion.word());
+                (suggestion.into_word(), script)
+            })
+            .collect()
+    }
+
+    /// Every dictionary entry with its distance to `word`, nearest first and
+    /// alphabetical within a distance. Ignores the candidate filters and
+    /// `max_distance`; this scans and sorts the whole dictionary, so it is
+    /// meant for analysis rather than correction.
+    pub fn rank_all(&self, word: &str) -> Vec<(&str, usize)> {
+        let mut ranked: Vec<(&str, usize)> = self
+            .dictionary
+            .words
+            .iter()
+            .map(|entry| (entry.as_str(), self.distance(word, entry)))
+            .collect();
+
+        // The dictionary is sorted, so a stable sort keeps ties alphabetical.
+        ranked.sort_by_key(|&(_, distance)| distance);
+        ranked
+    }
+
+    /// Dictionary entries eligible as corrections for `word`: those in the
+    /// same script (if enabled) that are not forbidden substitutions.
+    fn candidate_pool<'a>(&'a self, word: &str) -> impl Iterator<Item = &'a String> + 'a {
+        let max_distance = self.max_distance_for(word).unwrap_or(usize::MAX);
+        let mut candidates = self.source_candidates(word, max_distance);
+        if candidates.len() > self.max_candidates && !self.exhaustive {
+            candidates = self.most_promising(word, candidates);
+        }
+        self.filter_candidates(word, candidates)
+    }
+
+    /// Indices of the entries the [`CandidateSource`] might find within
+    /// `max_distance` of `word`, or of every entry if exhaustive.
+    fn source_candidates(&self, word: &str, max_distance: usize) -> Vec<usize> {
+        if self.exhaustive {
+            (0..self.dictionary.len()).collect()
+        } else {
+            self.source
+                .candidates(&self.dictionary.words, word, max_distance)
+        }
+    }
+
+    /// The `max_candidates` entries of `indices` most likely to be near
+    /// `word` by the cheap checks alone: a matching first letter first, then
+    /// the smallest length difference. Returned in ascending order so ties
+    /// still resolve alphabetically.
+    fn most_promising(&self, word: &str, mut indices: Vec<usize>) -> Vec<usize> {
+        if self.max_candidates == 0 {
+            return Vec::new();
+        }
+
+        let len = word.chars().count();
+        let first = word.chars().next();
+        let key = |&index: &usize| {
+            let candidate = &self.dictionary.words[index];
+            (
+                candidate.chars().next() != first,
+                candidate.chars().count().abs_diff(len),
+                index,
+            )
+        };
+        indices.select_nth_unstable_by_key(self.max_candidates - 1, key);
+        indices.truncate(self.max_candidates);
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Applies the script and forbidden-substitution filters to `indices`.
+    fn filter_candidates<'a, I>(
+        &'a self,
+        word: &str,
+        indices: I,
+    ) -> impl Iterator<Item = &'a String> + 'a
+    where
+        I: IntoIterator<Item = usize>,
+        I::IntoIter: 'a,
+    {
+        let script = if self.same_script {
+            Script::dominant(word)
+        } else {
+            Script::Common
+        };
+        let forbidden = self.forbidden.get(word);
+
+        indices
+            .into_iter()
+            .filter(move |&index| {
+                script == Script::Common || self.dictionary.scripts[index] == script
+            })
+            .map(|index| &self.dictionary.words[index])
+            .filter(move |candidate| forbidden.is_none_or(|targets| !targets.contains(*candidate)))
+    }
+
+    fn skips_numeric(&self, word: &str) -> bool {
+        self.ignore_numeric && is_numeric(word)
+    }
+
+    /// Whether `word` looks like something other than a misspelled word and
+    /// is left alone: a number, an acronym or a symbol-heavy token, per the
+    /// configured heuristics, or an accepted word. Forced corrections still
+    /// apply to it.
+    fn skips(&self, word: &str) -> bool {
+        if self.forced_correction(word).is_some() {
+            return false;
+        }
+
+        self.accepts(word)
+            || self.skips_numeric(word)
+            || (self.skip_acronyms && is_acronym(word))
+            || self
+                .max_symbol_ratio
+                .is_some_and(|ratio| symbol_ratio(word) > ratio)
+    }
+
+    /// The distance limit for `word`: the smaller of the absolute and the
+    /// length-scaled threshold, or `None` if neither is set.
+    fn max_distance_for(&self, word: &str) -> Option<usize> {
+        let scaled = self
+            .max_distance_ratio
+            .map(|ratio| (word.chars().count() as f64 * ratio).floor() as usize);
+
+        match (self.max_distance, scaled) {
+            (Some(max), Some(scaled)) => Some(max.min(scaled)),
+            (max, scaled) => max.or(scaled),
+        }
+    }
+
+    fn within_max_distance(&self, word: &str, distance: usize) -> bool {
+        self.max_distance_for(word)
+            .is_none_or(|max| distance <= max)
+    }
+
+    pub fn correct_word(&self, word: &str) -> String {
+        let mut corrected = String::new();
+        self.correct_word_into(word, &mut corrected);
+        corrected
+    }
+
+    /// Like [`SpellChecker::correct_word`], but clears `out` and writes the
+    /// correction into it so a caller correcting many words can reuse one
+    /// buffer. Known words, the common case, are copied straight into `out`;
+    /// words that need a search still allocate while searching.
+    pub fn correct_word_into(&self, word: &str, out: &mut String) {
+        self.correct_word_with_strategy(word, out);
+    }
+
+    /// Corrects `word` like [`SpellChecker::correct_word`] and records how
+    /// the result came about, for audit logs. Searches count candidates
+    /// once more on top of the correction itself.
+    pub fn decide(&self, word: &str) -> Decision {
+        let mut output = String::new();
+        let strategy = self.correct_word_with_strategy(word, &mut output);
+        self.decision(word, output, strategy)
+    }
+
+    /// The [`Decision`] for `word` corrected to `output` by `strategy`.
+    fn decision(&self, word: &str, output: String, strategy: Strategy) -> Decision {
+        let candidates = match strategy {
+            Strategy::Stem
+            | Strategy::Frequency
+            | Strategy::Nearest
+            | Strategy::Phonetic
+            | Strategy::Kept => self.candidate_pool(&self.lookup_key(word)).count(),
+            _ => 0,
+        };
+        let distance = if output == word {
+            0
+        } else {
+            self.edit_distance(word, &output)
+        };
+
+        Decision {
+            input: word.to_string(),
+            confidence: self.score(&output, distance),
+            output,
+            distance,
+            candidates,
+            strategy,
+        }
+    }
+
+    fn correct_word_with_strategy(&self, word: &str, out: &mut String) -> Strategy {
+        out.clear();
+
+        // The distance from "" is just the entry's length, so a scan would
+        // return the shortest dictionary word.
+        if word.is_empty() {
+            return Strategy::Empty;
+        }
+
+        if let Some(forced) = self.forced_correction(word) {
+            if self.ignore_case {
+                out.push_str(&match_case(word, forced));
+            } else {
+                out.push_str(forced);
+            }
+            return Strategy::Forced;
+        }
+
+        if self.skips(word) {
+            out.push_str(word);
+            return if self.accepts(word) {
+                Strategy::Accepted
+            } else {
+                Strategy::Skipped
+            };
+        }
+
+        if !self.ignore_case {
+            let key = compose(word);
+            if self.contains_word(&key) {
+                out.push_str(word);
+                return Strategy::Known;
+            }
+            let (corrected, strategy) = self.correct_cached(&key);
+            // A word left alone keeps the accents as written.
+            out.push_str(if corrected == key { word } else { &corrected });
+            return strategy;
+        }
+
+        if self.contains_exact(word) {
+            out.push_str(word);
+            return Strategy::Known;
+        }
+
+        if let Some(entry) = self.dictionary.folded(word) {
+            out.push_str(if self.normalize_case { entry } else { word });
+            return Strategy::Known;
+        }
+
+        let (corrected, strategy) = self.correct_cached(&fold(word));
+        out.push_str(&match_case(word, &corrected));
+        strategy
+    }
+
+    /// The form of `word` that lookups, searches and the cache are keyed
+    /// on: canonically composed, and lowercased when case is ignored.
+    fn lookup_key(&self, word: &str) -> String {
+        if self.ignore_case {
+            fold(word)
+        } else {
+            compose(word).into_owned()
+        }
+    }
+
+    fn correct_cached(&self, key: &str) -> (String, Strategy) {
+        let Some(cache) = &self.cache else {
+            return self.correct_normalized(key);
+        };
+
+        if let Some(corrected) = cache.lock().unwrap().get(key) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return corrected.clone();
+        }
+
+        let corrected = self.correct_normalized(key);
+        cache
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), corrected.clone());
+        corrected
+    }
+
+    fn correct_normalized(&self, word: &str) -> (String, Strategy) {
+        let (corrected, strategy) = self.choose_correction(word);
+
+        // A candidate differing only in whitespace would look like a no-op
+        // "correction".
+        if corrected != word && same_ignoring_whitespace(word, &corrected) {
+            return (word.to_string(), Strategy::Kept);
+        }
+        (corrected, strategy)
+    }
+
+    fn choose_correction(&self, word: &str) -> (String, Strategy) {
+        if self.contains_exact(word) {
+            return (word.to_string(), Strategy::Known);
+        }
+        if self.skips_numeric(word) {
+            return (word.to_string(), Strategy::Skipped);
+        }
+        if self.check_only {
+            return (word.to_string(), Strategy::CheckOnly);
+        }
+
+        if self.collapse_repeats
+            && let Some(collapsed) = collapsed_forms(word)
+                .into_iter()
+                .find(|form| self.contains_exact(form))
+        {
+            return (collapsed, Strategy::Collapsed);
+        }
+
+        self.searches.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(surface) = self.stem_match(word) {
+            return (surface.to_string(), Strategy::Stem);
+        }
+
+        let (best_match, min_distance) = self.nearest(word, self.candidate_pool(word));
+
+        if let Some(phonetic) = self.phonetic_match(word, &best_match) {
+            return (phonetic.to_string(), Strategy::Phonetic);
+        }
+
+        if !self.within_max_distance(word, min_distance) {
+            // The rejected candidate is still the nearest one, which tells the
+            // user how far the threshold would have to move.
+            if let Some(max) = self.max_distance_for(word)
+                && min_distance != usize::MAX
+            {
+                trace::event(format_args!(
+                    "kept '{}'; nearest: '{}' dist {} > max {}",
+                    word, best_match, min_distance, max
+                ));
+            }
+            return (word.to_string(), Strategy::Kept);
+        }
+
+        if min_distance == usize::MAX {
+            return (best_match, Strategy::Kept);
+        }
+        if self.tie_break() == TieBreak::Frequency {
+            return (best_match, Strategy::Frequency);
+        }
+        (best_match, Strategy::Nearest)
+    }
+
+    /// The first candidate at the smallest distance from `word`, or `word`
+    /// itself at `usize::MAX` if there are none. With
+    /// [`TieBreak::ShortestFirst`] the shortest of the nearest candidates is
+    /// taken instead, and with [`TieBreak::Frequency`] the most frequent.
+    /// Otherwise the scan stops at the first candidate no later one can
+    /// beat: one at the cheapest single edit.
+    fn nearest<'a, I>(&self, word: &str, candidates: I) -> (String, usize)
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        let tie_break = self.tie_break();
+        let cheapest_edit = self.edit_costs.map_or(1, |costs| {
+            costs.insertion.min(costs.deletion).min(costs.substitution)
+        });
+        // Lower ranks first among candidates at the same distance.
+        let rank = |candidate: &str| match tie_break {
+            TieBreak::Alphabetical => 0,
+            TieBreak::Frequency => u64::MAX - self.frequencies.get(candidate).copied().unwrap_or(0),
+            TieBreak::ShortestFirst => candidate.chars().count() as u64,
+        };
+        let mut best_match = word.to_string();
+        let mut min_distance = usize::MAX;
+        let mut best_rank = u64::MAX;
+
+        for correct_word in candidates {
+            // Only a candidate at most as far as the best so far can win.
+            let Some(distance) = self.distance_within(word, correct_word, min_distance) else {
+                continue;
+            };
+            let rank = rank(correct_word);
+
+            if (distance, rank) < (min_distance, best_rank) {
+                min_distance = distance;
+                best_rank = rank;
+                best_match = correct_word.clone();
+
+                if distance <= cheapest_edit && tie_break == TieBreak::Alphabetical {
+                    break;
+                }
+            }
+        }
+
+        (best_match, min_distance)
+    }
+
+    /// Compares the nearest-match search through the configured
+    /// [`CandidateSource`] with a scan of the whole dictionary, returning the
+    /// difference if they pick different corrections. Both sides apply the
+    /// same filters, distance limit and tie rule; stems, phonetic keys and
+    /// frequency ranking are left out since they are not part of the scan. A
+    /// correct source never disagrees, so this is a check on pruning bugs.
+    pub fn cross_check(&self, word: &str) -> Option<Disagreement> {
+        let word = self.lookup_key(word);
+        if word.is_empty()
+            || self.contains_word(&word)
+            || self.skips(&word)
+            || self.forced_correction(&word).is_some()
+        {
+            return None;
+        }
+
+        let within = |(candidate, distance): (String, usize)| {
+            if self.within_max_distance(&word, distance) {
+                (candidate, distance)
+            } else {
+                (word.clone(), distance)
+            }
+        };
+        let (fast, fast_distance) = within(self.nearest(&word, self.candidate_pool(&word)));
+        let all = self.filter_candidates(&word, 0..self.dictionary.len());
+        let (reference, reference_distance) = within(self.nearest(&word, all));
+
+        (fast != reference).then_some(Disagreement {
+            word,
+            fast,
+            fast_distance,
+            reference,
+            reference_distance,
+        })
+    }
+
+    /// [`SpellChecker::cross_check`] for every word token of `word_list`.
+    pub fn cross_check_word_list(&self, word_list: &WordList) -> Vec<Disagreement> {
+        word_list
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Word(word) => self.cross_check(word),
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+            .collect()
+    }
+
+    /// A [`Decision`] for every word token of `word_list`, as corrected by
+    /// [`SpellChecker::correct_word_list`]: line-level rules such as hints,
+    /// segmentation and the per-line correction limit are taken into account.
+    pub fn decide_word_list(&self, word_list: &WordList) -> Vec<Decision> {
+        let mut decisions = Vec::new();
+        self.correct_line(word_list, Some(&mut decisions));
+        decisions
+    }
+
+    /// Returns the dictionary's spelling of `word`, matched case-insensitively.
+    /// An exact match wins; otherwise the first entry in sorted order whose
+    /// lowercase form equals the input's is returned.
+    pub fn canonical(&self, word: &str) -> Option<&str> {
+        if let Ok(index) = self
+            .dictionary
+            .words
+            .binary_search_by(|entry| entry.as_str().cmp(word))
+        {
+            return Some(&self.dictionary.words[index]);
+        }
+
+        self.dictionary.folded(word)
+    }
+
+    /// Checks that every dictionary entry is recognized by `contains_word`,
+    /// contains no hidden characters and, if `check_correction` is set,
+    /// corrects to itself. Returns each failing entry with the reason.
+    pub fn self_test(&self, check_correction: bool) -> Vec<(&str, &'static str)> {
+        let mut failures = Vec::new();
+
+        for word in self.dictionary.words() {
+            if word
+                .chars()
+                .any(|c| c.is_control() || c.is_whitespace() || c == '\u{200b}' || c == '\u{feff}')
+            {
+                failures.push((word.as_str(), "contains hidden or whitespace characters"));
+            }
+            if !self.contains_word(word) {
+                failures.push((word.as_str(), "is not recognized by contains_word"));
+            } else if check_correction && self.correct_word(word) != *word {
+                failures.push((word.as_str(), "does not correct to itself"));
+            }
+        }
+
+        failures
+    }
+
+    /// Splits `word` into the fewest dictionary words (at least two, at most
+    /// `max_segments`), or `None` if segmentation is disabled or impossible.
+    pub fn segment(&self, word: &str) -> Option<Vec<String>> {
+        let max_segments = self.max_segments?;
+
+        let bounds: Vec<usize> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(word.len()))
+            .collect();
+
+        // best[j] = (segment count, start boundary) of the best split of the
+        // first j characters.
+        let mut best: Vec<Option<(usize, usize)>> = vec![None; bounds.len()];
+        best[0] = Some((0, 0));
+
+        for end in 1..bounds.len() {
+            for start in 0..end {
+                let Some((count, _)) = best[start] else {
+                    continue;
+                };
+                if count + 1 > max_segments
+                    || best[end].is_some_and(|(best_count, _)| best_count <= count + 1)
+                {
+                    continue;
+                }
+                if self.contains_exact(&word[bounds[start]..bounds[end]]) {
+                    best[end] = Some((count + 1, start));
+                }
+            }
+        }
+
+        let (count, _) = best[bounds.len() - 1]?;
+        if count < 2 {
+            return None;
+        }
+
+        let mut segments = Vec::with_capacity(count);
+        let mut end = bounds.len() - 1;
+        while end > 0 {
+            let (_, start) = best[end]?;
+            segments.push(word[bounds[start]..bounds[end]].to_string());
+            end = start;
+        }
+        segments.reverse();
+
+        Some(segments)
+    }
+
+    fn has_close_candidate(&self, word: &str) -> bool {
+        self.candidate_pool(word)
+            .any(|candidate| self.distance(word, candidate) <= 1)
+    }
+
+    /// Returns `true` if any word token of `word_list` is missing from the dictionary.
+    /// Only membership is checked, so no distance computation takes place.
+    pub fn needs_correction(&self, word_list: &WordList) -> bool {
+        word_list.tokens.iter().any(|token| match token {
+            Token::Word(word) => !self.contains_word(word) && !self.skips(word),
+            Token::Separator(_) | Token::Delimiter(_) => false,
+        })
+    }
+
+    /// The distance limit for the word at `index` under
+    /// [`SpellChecker::with_context_distance`], or `None` if the usual limit
+    /// applies.
+    fn context_max_distance(
+        &self,
+        word_list: &WordList,
+        index: usize,
+        word: &str,
+    ) -> Option<usize> {
+        if !self.context_distance || self.check_only {
+            return None;
+        }
+        let max = self.max_distance_for(word)?;
+
+        let (known, neighbours) = word_list
+            .tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(i, token)| match token {
+                Token::Word(neighbour) if i != index && !self.skips(neighbour) => {
+                    Some(self.contains_word(neighbour))
+                }
+                Token::Word(_) | Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+            .fold((0, 0), |(known, total), is_known| {
+                (known + usize::from(is_known), total + 1)
+            });
+
+        if neighbours == 0 {
+            None
+        } else if known == neighbours {
+            Some(max + 1)
+        } else if known * 2 < neighbours {
+            Some(max.saturating_sub(1))
+        } else {
+            None
+        }
+    }
+
+    /// Re-applies the distance limit `max` to `corrected`, the usual
+    /// correction of `word`: a correction beyond `max` is undone, and a
+    /// word kept only because of the usual limit is searched again.
+    fn limit_correction(&self, word: &str, corrected: String, max: usize) -> String {
+        if self.forced_correction(word).is_some() || self.contains_word(word) {
+            return corrected;
+        }
+        if corrected != word {
+            return if self.edit_distance(word, &corrected) > max {
+                word.to_string()
+            } else {
+                corrected
+            };
+        }
+
+        let key = self.lookup_key(word);
+        let candidates = self.source_candidates(&key, max);
+        let (nearest, distance) = self.nearest(&key, self.filter_candidates(&key, candidates));
+        if distance > max {
+            corrected
+        } else if self.ignore_case {
+            match_case(word, &nearest)
+        } else {
+            nearest
+        }
+    }
+
+    pub fn correct_word_list(&self, word_list: &WordList) -> WordList {
+        self.correct_word_list_tracked(word_list).0
+    }
+
+    /// Corrects every list, in order, without printing or touching files.
+    pub fn correct_all(&self, word_lists: &[WordList]) -> Vec<WordList> {
+        self.correct_all_tracked(word_lists, 1, |_| true, &AtomicBool::new(false))
+            .results
+            .into_iter()
+            .map(|(corrected, _)| corrected)
+            .collect()
+    }
+
+    /// Corrects the lists for which `select` returns `true` and passes the
+    /// others through unchanged, returning each result with its changes as
+    /// from [`SpellChecker::correct_word_list_tracked`].
+    ///
+    /// The work is split into `jobs` contiguous chunks corrected on their own
+    /// threads; results come back in input order whatever the number of jobs.
+    /// Once `cancel` is set each chunk stops before its next list, so a list
+    /// is never cut off halfway. Every list a chunk finished is kept and the
+    /// rest are passed through as written, so the output is always complete.
+    pub fn correct_all_tracked<F>(
+        &self,
+        word_lists: &[WordList],
+        jobs: usize,
+        select: F,
+        cancel: &AtomicBool,
+    ) -> CorrectionRun
+    where
+        F: Fn(&WordList) -> bool + Sync,
+    {
+        self.correct_chunks(
+            word_lists,
+            jobs,
+            select,
+            cancel,
+            None::<fn(&WordList, &[Decision])>,
+        )
+    }
+
+    /// Like [`SpellChecker::correct_all_tracked`], and also records a
+    /// [`Decision`] for every word of the selected lists, as from
+    /// [`SpellChecker::decide_word_list`]. `audit` is called on the calling
+    /// thread, in input order, with each finished list and its decisions as
+    /// soon as its chunk is done, so the records of a cancelled run cover
+    /// exactly the lists that were corrected.
+    pub fn correct_all_audited<F, A>(
+        &self,
+        word_lists: &[WordList],
+        jobs: usize,
+        select: F,
+        cancel: &AtomicBool,
+        audit: A,
+    ) -> CorrectionRun
+    where
+        F: Fn(&WordList) -> bool + Sync,
+        A: FnMut(&WordList, &[Decision]),
+    {
+        self.correct_chunks(word_lists, jobs, select, cancel, Some(audit))
+    }
+
+    fn correct_chunks<F, A>(
+        &self,
+        word_lists: &[WordList],
+        jobs: usize,
+        select: F,
+        cancel: &AtomicBool,
+        mut audit: Option<A>,
+    ) -> CorrectionRun
+    where
+        F: Fn(&WordList) -> bool + Sync,
+        A: FnMut(&WordList, &[Decision]),
+    {
+        type Corrected = (WordList, Vec<(String, String)>, Option<Vec<Decision>>);
+
+        let auditing = audit.is_some();
+        let chunk_size = word_lists.len().div_ceil(jobs.max(1)).max(1);
+        let correct_chunk = |chunk: &[WordList]| -> Vec<Corrected> {
+            chunk
+                .iter()
+                .map_while(|word_list| {
+                    if cancel.load(Ordering::Relaxed) {
+                        None
+                    } else if select(word_list) {
+                        let mut decisions = Vec::new();
+                        let (corrected, outcomes) =
+                            self.correct_line(word_list, auditing.then_some(&mut decisions));
+                        let changes = Self::changes(outcomes);
+                        Some((corrected, changes, auditing.then_some(decisions)))
+                    } else {
+                        Some((word_list.as_written(), Vec::new(), None))
+                    }
+                })
+                .collect()
+        };
+
+        let mut run = CorrectionRun {
+            results: Vec::with_capacity(word_lists.len()),
+            finished: Vec::with_capacity(word_lists.len()),
+        };
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = word_lists
+                .chunks(chunk_size)
+                .map(|chunk| (chunk, scope.spawn(|| correct_chunk(chunk))))
+                .collect();
+
+            for (chunk, handle) in handles {
+                let chunk_results = handle.join().expect("correction thread panicked");
+                let done = chunk_results.len();
+                for (word_list, (corrected, changes, decisions)) in chunk.iter().zip(chunk_results)
+                {
+                    if let (Some(audit), Some(decisions)) = (audit.as_mut(), decisions) {
+                        audit(word_list, &decisions);
+                    }
+                    run.results.push((corrected, changes));
+                }
+                run.results.extend(
+                    chunk[done..]
+                        .iter()
+                        .map(|word_list| (word_list.as_written(), Vec::new())),
+                );
+                run.finished
+                    .extend((0..chunk.len()).map(|index| index < done));
+            }
+        });
+        run
+    }
+
+    /// Corrects every list in order, calling `on_line` with the index, the
+    /// original and the corrected list as soon as each one is done, e.g. to
+    /// drive a progress display. Returns all corrected lists.
+    pub fn correct_word_lists_with<F>(
+        &self,
+        word_lists: &[WordList],
+        mut on_line: F,
+    ) -> Vec<WordList>
+    where
+        F: FnMut(usize, &WordList, &WordList),
+    {
+        word_lists
+            .iter()
+            .enumerate()
+            .map(|(index, word_list)| {
+                let corrected = self.correct_word_list(word_list);
+                on_line(index, word_list, &corrected);
+                corrected
+            })
+            .collect()
+    }
+
+    /// Corrects each word token of `word_list` in place and reports only the
+    /// ones that changed. Patches replace single tokens, so segmentation and
+    /// dropping of unknown words are not applied here.
+    pub fn diff_word_list(&self, word_list: &WordList) -> LineDiff {
+        let changes = word_list
+            .tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(index, token)| match token {
+                Token::Word(word) => {
+                    let corrected = self.correct_word(word);
+                    (corrected != *word).then(|| (index, word.clone(), corrected))
+                }
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+            .collect();
+
+        LineDiff {
+            id: word_list.id.clone(),
+            changes,
+        }
+    }
+
+    /// Share of the word tokens in `word_list` that are in the dictionary
+    /// (see [`SpellChecker::contains_word`]) or skipped (numbers and the
+    /// like), from membership checks only. An entry without words counts as
+    /// fully known.
+    pub fn known_ratio(&self, word_list: &WordList) -> f64 {
+        let (known, total) = word_list
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Word(word) => Some(self.contains_word(word) || self.skips(word)),
+                Token::Separator(_) | Token::Delimiter(_) => None,
+            })
+            .fold((0, 0), |(known, total), is_known| {
+                (known + usize::from(is_known), total + 1)
+            });
+
+        if total == 0 {
+            1.0
+        } else {
+            known as f64 / total as f64
+        }
+    }
+
+    /// Corrects `word_list` and also returns every `(original, replacement)`
+    /// pair for words that were changed. Segmented words report their parts
+    /// joined by spaces; dropped words are not reported.
+    pub fn correct_word_list_tracked(
+        &self,
+        word_list: &WordList,
+    ) -> (WordList, Vec<(String, String)>) {
+        let (corrected, outcomes) = self.correct_word_list_detailed(word_list);
+        (corrected, Self::changes(outcomes))
+    }
+
+    /// The `(original, replacement)` pairs of the corrected tokens.
+    fn changes(outcomes: Vec<TokenOutcome>) -> Vec<(String, String)> {
+        outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome {
+                TokenOutcome::Corrected { from, to, .. } => Some((from, to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Corrects `word_list` and reports what happened to each of its tokens,
+    /// one [`TokenOutcome`] per input token in order.
+    pub fn correct_word_list_detailed(
+        &self,
+        word_list: &WordList,
+    ) -> (WordList, Vec<TokenOutcome>) {
+        self.correct_line(word_list, None)
+    }
+
+    /// [`SpellChecker::correct_word_list_detailed`], also pushing a
+    /// [`Decision`] per word token onto `decisions` if given.
+    fn correct_line(
+        &self,
+        word_list: &WordList,
+        mut decisions: Option<&mut Vec<Decision>>,
+    ) -> (WordList, Vec<TokenOutcome>) {
+        let mut decide = |word: &str, output: &str, strategy: Strategy| {
+            if let Some(decisions) = decisions.as_mut() {
+                decisions.push(self.decision(word, output.to_string(), strategy));
+            }
+        };
+
+        if word_list.hints.is_empty()
+            && self
+                .skip_if_clean
+                .is_some_and(|ratio| self.known_ratio(word_list) >= ratio)
+        {
+            let outcomes = word_list
+                .tokens
+                .iter()
+                .map(|token| match token {
+                    Token::Word(word) => {
+                        decide(word, word, self.unchanged_strategy(word, Strategy::Clean));
+                        self.unchanged_outcome(word)
+                    }
+                    Token::Separator(_) | Token::Delimiter(_) => TokenOutcome::Separator,
+                })
+                .collect();
+            return (word_list.as_written(), outcomes);
+        }
+
+        let mut span = trace::span("correct_line");
+        let comparisons_before = self.comparisons.load(Ordering::Relaxed);
+        let mut corrected_tokens: Vec<Token> = Vec::with_capacity(word_list.tokens.len());
+        let mut outcomes = Vec::with_capacity(word_list.tokens.len());
+        let mut skip_separator = false;
+        let mut allowed = self
+            .max_corrections_per_line
+            .map(|limit| self.most_confident_corrections(word_list, limit));
+        // Puts back typographic characters normalized away while parsing.
+        let written = |index: usize, word: &str| match word_list.originals.get(&index) {
+            Some(original) => restore_typography(original, word),
+            None => word.to_string(),
+        };
+
+        for (index, token) in word_list.tokens.iter().enumerate() {
+            match token {
+                Token::Word(word) => {
+                    if let Some(hint) = word_list.hints.get(&index) {
+                        decide(word, hint, Strategy::Hint);
+                        outcomes.push(if hint == word {
+                            TokenOutcome::Unchanged
+                        } else {
+                            TokenOutcome::Corrected {
+                                from: word.clone(),
+                                to: hint.clone(),
+                                distance: self.edit_distance(word, hint),
+                            }
+                        });
+                        corrected_tokens.push(Token::Word(hint.clone()));
+                        continue;
+                    }
+
+                    let precomputed = allowed.as_mut().map(|allowed| allowed.remove(&index));
+                    if matches!(precomputed, Some(None)) || self.skips(word) {
+                        decide(word, word, self.unchanged_strategy(word, Strategy::Limited));
+                        corrected_tokens.push(Token::Word(written(index, word)));
+                        outcomes.push(self.unchanged_outcome(word));
+                        continue;
+                    }
+
+                    if self.max_segments.is_some()
+                        && !self.check_only
+                        && self.forced_correction(word).is_none()
+                        && !self.contains_word(word)
+                        && !self.has_close_candidate(word)
+                        && let Some(segments) = self.segment(word)
+                    {
+                        let to = segments.join(" ");
+                        decide(word, &to, Strategy::Segmented);
+                        outcomes.push(TokenOutcome::Corrected {
+                            distance: self.edit_distance(word, &to),
+                            from: word.clone(),
+                            to,
+                        });
+                        for (i, segment) in segments.into_iter().enumerate() {
+                            if i > 0 {
+                                corrected_tokens.push(Token::Separator(' '));
+                            }
+                            corrected_tokens.push(Token::Word(segment));
+                        }
+                        continue;
+                    }
+
+                    let (mut corrected, mut strategy) =
+                        precomputed.flatten().unwrap_or_else(|| {
+                            let mut corrected = String::new();
+                            let strategy = self.correct_word_with_strategy(word, &mut corrected);
+                            (corrected, strategy)
+                        });
+                    if let Some(max) = self.context_max_distance(word_list, index, word) {
+                        let limited = self.limit_correction(word, corrected.clone(), max);
+                        if limited != corrected {
+                            strategy = if limited == *word {
+                                Strategy::Kept
+                            } else {
+                                Strategy::Nearest
+                            };
+                            corrected = limited;
+                        }
+                    }
+
+                    if self.drop_unknown && !self.contains_word(&corrected) {
+                        // Take one neighbouring separator with the dropped word so
+                        // "a xx b" becomes "a b" rather than "a  b".
+                        if matches!(
+                            corrected_tokens.last(),
+                            Some(Token::Separator(_) | Token::Delimiter(_))
+                        ) {
+                            corrected_tokens.pop();
+                        } else {
+                            skip_separator = true;
+                        }
+                        decide(word, "", Strategy::Dropped);
+                        outcomes.push(TokenOutcome::Dropped);
+                        continue;
+                    }
+
+                    decide(word, &corrected, strategy);
+                    if corrected == *word {
+                        outcomes.push(self.unchanged_outcome(word));
+                    } else {
+                        let distance = self.edit_distance(word, &corrected);
+                        outcomes.push(TokenOutcome::Corrected {
+                            from: word.clone(),
+                            to: corrected.clone(),
+                            distance,
+                        });
+                        if self.annotate_distance {
+                            corrected_tokens.push(Token::Word(format!(
+                                "{}[{}]",
+                                written(index, &corrected),
+                                distance
+                            )));
+                            continue;
+                        }
+                    }
+                    corrected_tokens.push(Token::Word(written(index, &corrected)));
+                }
+                Token::Separator(_) | Token::Delimiter(_) => {
+                    outcomes.push(TokenOutcome::Separator);
+                    if skip_separator {
+                        skip_separator = false;
+                        continue;
+                    }
+                    corrected_tokens.push(token.clone());
+                }
+            }
+        }
+
+        let corrected = WordList {
+            id: word_list.id.clone(),
+            id_separator: word_list.id_separator.clone(),
+            tokens: corrected_tokens,
+            leading: word_list.leading.clone(),
+            trailing: word_list.trailing.clone(),
+            line_number: word_list.line_number,
+            hints: HashMap::new(),
+            originals: HashMap::new(),
+        };
+
+        span.record("id", &word_list.id);
+        span.record(
+            "changes",
+            outcomes
+                .iter()
+                .filter(|outcome| matches!(outcome, TokenOutcome::Corrected { .. }))
+                .count(),
+        );
+        span.record(
+            "comparisons",
+            self.comparisons.load(Ordering::Relaxed) - comparisons_before,
+        );
+
+        (corrected, outcomes)
+    }
+
+    /// The strategy for a word left as written without a search: `otherwise`
+    /// unless it is known or skipped anyway.
+    fn unchanged_strategy(&self, word: &str, otherwise: Strategy) -> Strategy {
+        if self.skips(word) {
+            if self.accepts(word) {
+                Strategy::Accepted
+            } else {
+                Strategy::Skipped
+            }
+        } else if self.contains_word(word) {
+            Strategy::Known
+        } else {
+            otherwise
+        }
+    }
+
+    /// The outcome for a word left as written.
+    fn unchanged_outcome(&self, word: &str) -> TokenOutcome {
+        if self.contains_word(word) || self.skips(word) {
+            TokenOutcome::Unchanged
+        } else {
+            TokenOutcome::NoSuggestion
+        }
+    }
+}
+
+/// Digits with optional sign, decimal and grouping characters ("2024",
+/// "3.14", "-5", "12,000").
+pub(crate) fn is_numeric(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+'))
+}
+
+/// More than one letter and no lowercase ones ("HTTP", "CO2").
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() > 1 && !word.chars().any(char::is_lowercase)
+}
+
+/// Every way of shortening each run of three or more identical letters in
+/// `word` to one or two, longest forms first and in order otherwise. Empty
+/// if `word` has no such run, or too many to try every combination.
+fn collapsed_forms(word: &str) -> Vec<String> {
+    const MAX_RUNS: usize = 8;
+
+    let mut runs: Vec<(char, usize)> = Vec::new();
+    for c in word.chars() {
+        match runs.last_mut() {
+            Some((previous, count)) if *previous == c => *count += 1,
+            _ => runs.push((c, 1)),
+        }
+    }
+
+    let collapsible = |&(c, count): &(char, usize)| count >= 3 && c.is_alphabetic();
+    let collapsible_runs = runs.iter().filter(|run| collapsible(run)).count();
+    if collapsible_runs == 0 || collapsible_runs > MAX_RUNS {
+        return Vec::new();
+    }
+
+    let mut forms = vec![String::new()];
+    for run @ &(c, count) in &runs {
+        if collapsible(run) {
+            forms = forms
+                .into_iter()
+                .flat_map(|form| [2, 1].map(|n| format!("{}{}", form, c.to_string().repeat(n))))
+                .collect();
+        } else {
+            for form in &mut forms {
+                form.extend(std::iter::repeat_n(c, count));
+            }
+        }
+    }
+
+    forms.sort_by_key(|form| std::cmp::Reverse(form.chars().count()));
+    forms
+}
+
+fn same_ignoring_whitespace(a: &str, b: &str) -> bool {
+    let non_whitespace = |text: &str| {
+        text.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+    };
+    non_whitespace(a) == non_whitespace(b)
+}
+
+/// Share of characters in `word` that are not letters.
+fn symbol_ratio(word: &str) -> f64 {
+    let total = word.chars().count();
+    let symbols = word.chars().filter(|c| !c.is_alphabetic()).count();
+    symbols as f64 / total.max(1) as f64
+}
+
+/// Re-applies the case pattern of `original` to `corrected`: all-uppercase and
+/// capitalized originals are reproduced, anything else keeps the dictionary's
+/// spelling.
+fn match_case(original: &str, corrected: &str) -> String {
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+
+    match letters.as_slice() {
+        [first, rest @ ..]
+            if first.is_uppercase()
+                && !rest.is_empty()
+                && rest.iter().all(|c| c.is_uppercase()) =>
+        {
+            corrected.to_uppercase()
+        }
+        [first, rest @ ..] if first.is_uppercase() && rest.iter().all(|c| c.is_lowercase()) => {
+            let mut chars = corrected.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        _ => corrected.to_string(),
+    }
+}
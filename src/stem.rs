@@ -0,0 +1,44 @@
+/// A light suffix-stripping stemmer in the spirit of Porter's first step.
+/// It only needs to map regular English inflections of a word onto the same
+/// key ("running", "runing", "runs" -> "run"); the key itself is never shown
+/// to users.
+pub fn stem(word: &str) -> String {
+    let mut stem = word.to_lowercase();
+
+    if let Some(base) = stem.strip_suffix("ies")
+        && base.chars().count() >= 2
+    {
+        stem = format!("{}y", base);
+    } else if let Some(base) = stem.strip_suffix("sses") {
+        stem = format!("{}ss", base);
+    } else {
+        for suffix in ["ingly", "edly", "ing", "ed", "ly", "es", "s"] {
+            if suffix == "s" && stem.ends_with("ss") {
+                break;
+            }
+            if let Some(base) = stem.strip_suffix(suffix)
+                && base.chars().count() >= 3
+            {
+                stem = base.to_string();
+                if matches!(suffix, "ingly" | "edly" | "ing" | "ed") {
+                    undouble(&mut stem);
+                }
+                break;
+            }
+        }
+    }
+
+    stem
+}
+
+/// "runn" -> "run", but leave "ll", "ss" and "zz" alone ("fall", "miss").
+fn undouble(stem: &mut String) {
+    let mut chars = stem.chars().rev();
+    if let (Some(last), Some(previous)) = (chars.next(), chars.next())
+        && last == previous
+        && last.is_alphabetic()
+        && !matches!(last, 'a' | 'e' | 'i' | 'o' | 'u' | 'l' | 's' | 'z')
+    {
+        stem.pop();
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::encoding::strip_bom;
+use crate::error::SpellError;
+use crate::spell_checker::SpellChecker;
+use crate::word_list::{ParseOptions, Token, WordList};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorrectionStats {
+    /// Entries parsed and written to the output.
+    pub lines: usize,
+    /// Word tokens seen across all entries.
+    pub words: usize,
+    /// Entries whose corrected form differs from the input.
+    pub changed_lines: usize,
+    /// The run stopped correcting when cancelled. Every entry counted in `lines`
+    /// was written in full and the remaining input was copied unchanged.
+    pub interrupted: bool,
+    /// Errors of the lines skipped in lenient mode, in line order.
+    pub skipped: Vec<SpellError>,
+}
+
+/// Reads entries line by line from `input`, corrects them and writes each
+/// corrected entry to `output` as soon as it is ready. Once `cancel` is set,
+/// e.g. from a Ctrl-C handler, correction stops between entries and the rest
+/// of the input is copied through as written, so the output is never cut
+/// short.
+pub fn correct_stream<R: BufRead, W: Write>(
+    checker: &SpellChecker,
+    input: R,
+    mut output: W,
+    options: &ParseOptions,
+    cancel: &AtomicBool,
+) -> Result<CorrectionStats, SpellError> {
+    let mut stats = CorrectionStats::default();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| SpellError::Io(format!("Failed to read: {}", e)))?;
+        let line = if i == 0 { strip_bom(&line) } else { &line };
+
+        if cancel.load(Ordering::Relaxed) {
+            stats.interrupted = true;
+            writeln!(output, "{}", line)
+                .map_err(|e| SpellError::Io(format!("Failed to write: {}", e)))?;
+            continue;
+        }
+
+        let Some(word_list) = WordList::parse_entry(i + 1, line, options, &mut stats.skipped)?
+        else {
+            continue;
+        };
+        let corrected = checker.correct_word_list(&word_list);
+
+        stats.lines += 1;
+        stats.words += word_list
+            .tokens
+            .iter()
+            .filter(|token| matches!(token, Token::Word(_)))
+            .count();
+        if corrected.tokens != word_list.tokens {
+            stats.changed_lines += 1;
+        }
+
+        writeln!(output, "{}", corrected)
+            .map_err(|e| SpellError::Io(format!("Failed to write: {}", e)))?;
+    }
+
+    output
+        .flush()
+        .map_err(|e| SpellError::Io(format!("Failed to write: {}", e)))?;
+
+    Ok(stats)
+}
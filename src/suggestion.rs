@@ -0,0 +1,65 @@
+use std::cmp::Ordering;
+
+/// A candidate correction for a word, as returned by
+/// [`SpellChecker::correct_word_candidates`](crate::SpellChecker::correct_word_candidates).
+///
+/// Suggestions order by distance, then by higher confidence, then
+/// alphabetically, so sorting a list of them is deterministic.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    word: String,
+    distance: usize,
+    confidence: f64,
+}
+
+impl Suggestion {
+    pub fn new(word: String, distance: usize, confidence: f64) -> Self {
+        Suggestion {
+            word,
+            distance,
+            confidence,
+        }
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    pub fn into_word(self) -> String {
+        self.word
+    }
+
+    /// Edit distance from the misspelled word.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// The candidate's [`SpellChecker::score`](crate::SpellChecker::score);
+    /// higher is likelier.
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+}
+
+impl Ord for Suggestion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .cmp(&other.distance)
+            .then(other.confidence.total_cmp(&self.confidence))
+            .then_with(|| self.word.cmp(&other.word))
+    }
+}
+
+impl PartialOrd for Suggestion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Suggestion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Suggestion {}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+/// Counts how often each `(original, corrected)` pair occurred in a run.
+#[derive(Debug, Clone, Default)]
+pub struct CorrectionSummary {
+    counts: HashMap<(String, String), usize>,
+}
+
+impl CorrectionSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, original: &str, corrected: &str) {
+        *self
+            .counts
+            .entry((original.to_string(), corrected.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// Total number of corrections recorded.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// The `k` most frequent pairs, most frequent first. Equal counts are
+    /// ordered alphabetically so the report is stable between runs.
+    pub fn top(&self, k: usize) -> Vec<(&str, &str, usize)> {
+        let mut pairs: Vec<(&str, &str, usize)> = self
+            .counts
+            .iter()
+            .map(|((original, corrected), &count)| (original.as_str(), corrected.as_str(), count))
+            .collect();
+
+        pairs.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)).then(a.1.cmp(b.1)));
+        pairs.truncate(k);
+        pairs
+    }
+}
+
+/// Corrections counted by edit distance in the buckets 0, 1, 2 and 3+.
+#[derive(Debug, Clone, Default)]
+pub struct DistanceBuckets {
+    counts: [usize; 4],
+}
+
+impl DistanceBuckets {
+    pub const LABELS: [&'static str; 4] = ["0", "1", "2", "3+"];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index into [`DistanceBuckets::LABELS`] for a correction at `distance`.
+    pub fn bucket(distance: usize) -> usize {
+        distance.min(3)
+    }
+
+    pub fn add(&mut self, distance: usize) {
+        self.counts[Self::bucket(distance)] += 1;
+    }
+
+    pub fn counts(&self) -> [usize; 4] {
+        self.counts
+    }
+}
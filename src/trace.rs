@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// Environment variable that enables tracing when set to anything but `0`.
+pub const TRACE_ENV: &str = "SPELL_TRACE";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Enables tracing if [`TRACE_ENV`] is set.
+pub fn init_from_env() {
+    if std::env::var(TRACE_ENV).is_ok_and(|value| value != "0") {
+        set_enabled(true);
+    }
+}
+
+/// Prints a one-off diagnostic line to stderr when tracing is enabled.
+pub fn event<M: Display>(message: M) {
+    if enabled() {
+        eprintln!("[trace] {}", message);
+    }
+}
+
+/// A timed section of the pipeline. When tracing is enabled it prints its
+/// name, recorded fields and elapsed time to stderr on drop; otherwise it
+/// does nothing beyond reading the clock.
+pub struct Span {
+    name: &'static str,
+    fields: String,
+    start: Instant,
+}
+
+pub fn span(name: &'static str) -> Span {
+    Span {
+        name,
+        fields: String::new(),
+        start: Instant::now(),
+    }
+}
+
+impl Span {
+    pub fn record<V: Display>(&mut self, key: &str, value: V) {
+        if enabled() {
+            self.fields.push_str(&format!(" {}={}", key, value));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if enabled() {
+            eprintln!(
+                "[trace] {}{} elapsed={:?}",
+                self.name,
+                self.fields,
+                self.start.elapsed()
+            );
+        }
+    }
+}
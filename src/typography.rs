@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+/// What to do with curly quotes and dashes from word processors, which
+/// otherwise keep "don’t" from matching a dictionary entry "don't".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TypographyPolicy {
+    /// Leave them as written.
+    #[default]
+    Keep,
+    /// Replace them with their ASCII equivalents, in the output too.
+    Normalize,
+    /// Replace them for matching, then put the original characters back into
+    /// the corrected words.
+    Restore,
+}
+
+/// The ASCII stand-in for a typographic character, if it is one. Every
+/// mapping is one character to one character, so restoring is positional.
+fn ascii_equivalent(c: char) -> Option<char> {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' => Some('\''),
+        '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{201f}' => Some('"'),
+        '\u{2010}'..='\u{2015}' => Some('-'),
+        _ => None,
+    }
+}
+
+/// Replaces curly quotes and dashes in `text` with `'`, `"` and `-`.
+pub fn normalize_typography(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| ascii_equivalent(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .map(|c| ascii_equivalent(c).unwrap_or(c))
+            .collect(),
+    )
+}
+
+/// Puts the typographic characters of `original` back into `corrected`, in
+/// order: each one replaces the next matching ASCII character. "don’t"
+/// corrected to "don't" comes back as "don’t". Characters with nothing left
+/// to match are dropped along with the edit that removed them.
+pub fn restore_typography(original: &str, corrected: &str) -> String {
+    let mut pending = original
+        .chars()
+        .filter_map(|c| ascii_equivalent(c).map(|ascii| (ascii, c)))
+        .peekable();
+
+    corrected
+        .chars()
+        .map(|c| match pending.peek() {
+            Some(&(ascii, typographic)) if ascii == c => {
+                pending.next();
+                typographic
+            }
+            _ => c,
+        })
+        .collect()
+}
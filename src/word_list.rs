@@ -0,0 +1,723 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use crate::encoding::{Encoding, strip_bom};
+use crate::error::SpellError;
+use crate::front_matter::FrontMatter;
+use crate::sanitize::ControlCharPolicy;
+use crate::typography::{TypographyPolicy, normalize_typography};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Word(String),
+    Separator(char),
+    /// A multi-character delimiter from [`ParseOptions::delimiters`].
+    Delimiter(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct WordList {
+    pub id: String,
+    /// The spaces and tabs between the ID and the first word, written back
+    /// verbatim so untouched lines stay byte-identical.
+    pub id_separator: String,
+    pub tokens: Vec<Token>,
+    /// Whitespace before the ID and after the last token, written back
+    /// verbatim like [`WordList::id_separator`]. Empty for entries not
+    /// parsed from a whole input line.
+    pub leading: String,
+    pub trailing: String,
+    /// 1-based line of the input this entry was parsed from.
+    pub line_number: usize,
+    /// Token index -> correction given inline in the input, see
+    /// [`ParseOptions::hint_markers`]. The markup itself is not kept.
+    pub hints: HashMap<usize, String>,
+    /// Token index -> the word as written, for words whose typographic
+    /// characters were normalized under [`TypographyPolicy::Restore`].
+    pub originals: HashMap<usize, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorMode {
+    /// Split the word here and correct each side independently ("and/or").
+    Split,
+    /// Keep the character inside the surrounding token ("12/25").
+    Keep,
+}
+
+/// Which separator survives when a run of consecutive separators is merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorMerge {
+    First,
+    Last,
+}
+
+/// Characters allowed inside a word. Anything else acts as a separator.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum WordChars {
+    /// Every character that is not a configured separator.
+    #[default]
+    Any,
+    Letters,
+    Alphanumeric,
+    /// Letters, digits and apostrophes, so contractions stay whole.
+    AlphanumericApostrophe,
+    Custom(fn(char) -> bool),
+}
+
+impl WordChars {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            WordChars::Any => true,
+            WordChars::Letters => c.is_alphabetic(),
+            WordChars::Alphanumeric => c.is_alphanumeric(),
+            WordChars::AlphanumericApostrophe => c.is_alphanumeric() || c == '\'',
+            WordChars::Custom(predicate) => predicate(c),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Lines longer than this many bytes are rejected. `None` means unlimited.
+    pub max_line_length: Option<usize>,
+    /// Skip invalid lines with a warning instead of failing the whole file.
+    pub lenient: bool,
+    /// Collapse the whitespace between the ID and the first word so the output
+    /// always has a single space there. When `false`, the original spaces and
+    /// tabs are kept in [`WordList::id_separator`] and reproduced verbatim.
+    pub collapse_id_separator: bool,
+    /// Take the ID to be every digit up to the first space or tab ("12",
+    /// "123456") instead of exactly four characters.
+    pub variable_width_ids: bool,
+    /// Characters with configured separator behavior. Characters not listed
+    /// are always part of a word.
+    pub separators: Vec<(char, SeparatorMode)>,
+    /// Strings such as `"::"` or `" | "` that separate words as a whole. At
+    /// each position the longest matching delimiter wins, ahead of the
+    /// single-character separators. Empty by default.
+    pub delimiters: Vec<String>,
+    /// Treat simple angle-bracket tags such as `<b>` or `</a>` as opaque
+    /// delimiters, so the words between them are still corrected.
+    pub preserve_tags: bool,
+    /// Markers around an inline correction hint at the end of a word, e.g.
+    /// `("{->", "}")` for `teh{->the}`. The word is corrected to the hint
+    /// without a search, and the markup is removed. Hints cannot contain
+    /// separators. `None` (the default) reads such markup as part of the word.
+    pub hint_markers: Option<(String, String)>,
+    pub word_chars: WordChars,
+    /// Encoding of input files read through [`WordList::read_and_get`].
+    pub encoding: Encoding,
+    /// Handling of control characters inside word tokens.
+    pub control_chars: ControlCharPolicy,
+    /// Handling of curly quotes and dashes, applied before tokenizing.
+    pub typography: TypographyPolicy,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_line_length: None,
+            lenient: false,
+            collapse_id_separator: false,
+            variable_width_ids: false,
+            separators: vec![(' ', SeparatorMode::Split), ('/', SeparatorMode::Split)],
+            delimiters: Vec::new(),
+            preserve_tags: false,
+            hint_markers: None,
+            word_chars: WordChars::Any,
+            encoding: Encoding::Utf8,
+            control_chars: ControlCharPolicy::Strip,
+            typography: TypographyPolicy::Keep,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Sets the behavior of `separator`, replacing any earlier setting.
+    pub fn set_separator(&mut self, separator: char, mode: SeparatorMode) {
+        match self.separators.iter_mut().find(|(c, _)| *c == separator) {
+            Some(entry) => entry.1 = mode,
+            None => self.separators.push((separator, mode)),
+        }
+    }
+
+    /// The configured separators that split words.
+    pub fn split_separators(&self) -> Vec<char> {
+        self.separators
+            .iter()
+            .filter(|&&(_, mode)| mode == SeparatorMode::Split)
+            .map(|&(separator, _)| separator)
+            .collect()
+    }
+
+    fn splits_on(&self, c: char) -> bool {
+        self.separators
+            .iter()
+            .any(|&(separator, mode)| separator == c && mode == SeparatorMode::Split)
+    }
+
+    /// Spaces and tabs, and any other whitespace configured to split words:
+    /// the characters between the ID and the first word.
+    pub(crate) fn is_id_separator(&self, c: char) -> bool {
+        c == ' ' || c == '\t' || (c.is_whitespace() && self.splits_on(c))
+    }
+
+    /// Configured separators follow their mode; any other character splits
+    /// words only if it falls outside the word character class.
+    pub fn is_separator(&self, c: char) -> bool {
+        match self
+            .separators
+            .iter()
+            .find(|&&(separator, _)| separator == c)
+        {
+            Some(&(_, mode)) => mode == SeparatorMode::Split,
+            None => !self.word_chars.contains(c),
+        }
+    }
+}
+
+impl WordList {
+    /// 1-based line of the input this entry was parsed from, counting blank
+    /// and skipped lines and any front matter. Not part of the written entry.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn read_and_get<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Vec<WordList>, SpellError> {
+        Self::read_with_skipped(path, options).map(|(entries, _)| entries)
+    }
+
+    /// Like [`WordList::read_and_get`], also returning the errors of the
+    /// lines skipped in lenient mode so the caller can report them.
+    #[cfg(feature = "fs")]
+    pub fn read_with_skipped<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<(Vec<WordList>, Vec<SpellError>), SpellError> {
+        let content = options.encoding.read_file(path)?;
+        Self::parse_content_with_skipped(&content, options)
+    }
+
+    pub fn parse_content(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<Vec<WordList>, SpellError> {
+        Self::parse_content_with_skipped(content, options).map(|(entries, _)| entries)
+    }
+
+    /// Like [`WordList::parse_content`], also returning the errors of the
+    /// lines skipped in lenient mode, in line order.
+    pub fn parse_content_with_skipped(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<(Vec<WordList>, Vec<SpellError>), SpellError> {
+        let content = strip_bom(content);
+        let body_start = FrontMatter::body_start(content)?;
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+
+        for (i, line) in content.lines().enumerate().skip(body_start) {
+            if let Some(entry) = Self::parse_entry(i + 1, line, options, &mut skipped)? {
+                entries.push(entry);
+            }
+        }
+
+        if entries.is_empty() {
+            Err(SpellError::NoEntries)
+        } else {
+            Ok((entries, skipped))
+        }
+    }
+
+    /// Checks every line of the file without correcting anything, collecting
+    /// all problems instead of stopping at the first one.
+    #[cfg(feature = "fs")]
+    pub fn validate_file<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<(), Vec<SpellError>> {
+        let content = options.encoding.read_file(path).map_err(|e| vec![e])?;
+        Self::validate_content(&content, options)
+    }
+
+    pub fn validate_content(content: &str, options: &ParseOptions) -> Result<(), Vec<SpellError>> {
+        let mut errors = Vec::new();
+        let content = strip_bom(content);
+        let body_start = FrontMatter::body_start(content).map_err(|e| vec![e])?;
+        let mut valid_entries = 0;
+
+        for (i, line) in content.lines().enumerate().skip(body_start) {
+            // Lenient options report invalid lines through `errors` as well.
+            match Self::parse_entry(i + 1, line, options, &mut errors) {
+                Ok(Some(_)) => valid_entries += 1,
+                Ok(None) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if valid_entries == 0 && errors.is_empty() {
+            errors.push(SpellError::NoEntries);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parses one raw input line. Blank lines yield `Ok(None)`, as do invalid
+    /// lines in lenient mode, whose errors are added to `skipped`.
+    pub fn parse_entry(
+        line_number: usize,
+        line: &str,
+        options: &ParseOptions,
+        skipped: &mut Vec<SpellError>,
+    ) -> Result<Option<WordList>, SpellError> {
+        let Some((leading, entry, trailing)) = split_line(line, options) else {
+            return Ok(None);
+        };
+
+        let parsed = Self::check_line_length(line_number, entry, options)
+            .and_then(|_| Self::parse_line(line_number, entry, options));
+
+        match parsed {
+            Ok(mut entry) => {
+                entry.leading = leading.to_string();
+                entry.trailing = trailing.to_string();
+                Ok(Some(entry))
+            }
+            Err(e) if options.lenient => {
+                skipped.push(e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses free-form text as a single entry with no ID, for documents
+    /// that do not follow the `ID words` line format. Line breaks become
+    /// separators, so writing the entry back reproduces the text's layout.
+    /// Front matter is not recognized; the whole text is corrected.
+    pub fn parse_freeform(content: &str, options: &ParseOptions) -> Result<WordList, SpellError> {
+        let content = strip_bom(content);
+        let mut options = options.clone();
+        options.set_separator('\n', SeparatorMode::Split);
+        options.set_separator('\r', SeparatorMode::Split);
+
+        if options.control_chars == ControlCharPolicy::Reject
+            && let Some((i, line)) = content.lines().enumerate().find(|(_, line)| {
+                line.chars()
+                    .any(|c| c.is_control() && !options.splits_on(c))
+            })
+        {
+            return Err(SpellError::InvalidLine {
+                line: i + 1,
+                reason: format!("contains control characters: {:?}", line),
+            });
+        }
+
+        let (mut tokens, mut originals) = Self::tokenize(content, &options);
+        if !tokens.iter().any(|token| matches!(token, Token::Word(_))) {
+            return Err(SpellError::NoEntries);
+        }
+        let hints = Self::extract_hints(&mut tokens, &options);
+        originals.retain(|index, _| !hints.contains_key(index));
+
+        Ok(WordList {
+            id: String::new(),
+            id_separator: String::new(),
+            tokens,
+            leading: String::new(),
+            trailing: String::new(),
+            line_number: 1,
+            hints,
+            originals,
+        })
+    }
+
+    fn check_line_length(
+        line_number: usize,
+        line: &str,
+        options: &ParseOptions,
+    ) -> Result<(), SpellError> {
+        match options.max_line_length {
+            Some(max) if line.len() > max => Err(SpellError::LineTooLong {
+                line: line_number,
+                length: line.len(),
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn parse_line(
+        line_number: usize,
+        line: &str,
+        options: &ParseOptions,
+    ) -> Result<WordList, SpellError> {
+        let id_len = if options.variable_width_ids {
+            line.find([' ', '\t']).unwrap_or(line.len())
+        } else {
+            4
+        };
+
+        if line.len() <= id_len {
+            return Err(SpellError::InvalidLine {
+                line: line_number,
+                reason: format!("is too short: '{}'", line),
+            });
+        }
+
+        let id = match line.get(0..id_len) {
+            Some(id) if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) => id.to_string(),
+            _ => {
+                return Err(SpellError::InvalidLine {
+                    line: line_number,
+                    reason: format!(
+                        "has invalid ID: '{}'",
+                        line.chars().take(id_len.max(1)).collect::<String>()
+                    ),
+                });
+            }
+        };
+
+        if !line[id_len..].starts_with([' ', '\t']) {
+            return Err(SpellError::InvalidLine {
+                line: line_number,
+                reason: format!("is missing the separator after the ID: '{}'", line),
+            });
+        }
+
+        let rest = &line[id_len..];
+        let words_part = rest.trim_start_matches(|c| options.is_id_separator(c));
+        let id_separator = if options.collapse_id_separator {
+            " "
+        } else {
+            &rest[..rest.len() - words_part.len()]
+        };
+
+        if options.control_chars == ControlCharPolicy::Reject
+            && words_part.chars().any(char::is_control)
+        {
+            return Err(SpellError::InvalidLine {
+                line: line_number,
+                reason: format!("contains control characters: {:?}", line),
+            });
+        }
+
+        let (mut tokens, mut originals) = Self::tokenize(words_part, options);
+
+        if !tokens.iter().any(|token| matches!(token, Token::Word(_))) {
+            return Err(SpellError::InvalidLine {
+                line: line_number,
+                reason: format!("has no valid words: '{}'", line),
+            });
+        }
+        let hints = Self::extract_hints(&mut tokens, options);
+        originals.retain(|index, _| !hints.contains_key(index));
+
+        Ok(WordList {
+            id,
+            id_separator: id_separator.to_string(),
+            tokens,
+            leading: String::new(),
+            trailing: String::new(),
+            line_number,
+            hints,
+            originals,
+        })
+    }
+
+    /// [`WordList::parse_tokens`] after applying [`ParseOptions::typography`],
+    /// along with the original spelling of each word it changed when those
+    /// are to be restored. If the normalized characters were separators, the
+    /// tokens no longer line up and nothing is restored.
+    fn tokenize(text: &str, options: &ParseOptions) -> (Vec<Token>, HashMap<usize, String>) {
+        if options.typography == TypographyPolicy::Keep {
+            return (Self::parse_tokens(text, options), HashMap::new());
+        }
+
+        let normalized = normalize_typography(text);
+        let tokens = Self::parse_tokens(&normalized, options);
+        if options.typography == TypographyPolicy::Normalize
+            || matches!(normalized, Cow::Borrowed(_))
+        {
+            return (tokens, HashMap::new());
+        }
+
+        let written = Self::parse_tokens(text, options);
+        if written.len() != tokens.len() {
+            return (tokens, HashMap::new());
+        }
+        let originals = written
+            .into_iter()
+            .zip(&tokens)
+            .enumerate()
+            .filter_map(|(index, pair)| match pair {
+                (Token::Word(original), Token::Word(word)) if original != *word => {
+                    Some((index, original))
+                }
+                _ => None,
+            })
+            .collect();
+        (tokens, originals)
+    }
+
+    /// Strips hint markup from the word tokens ending in one and returns the
+    /// hints by token index. A marker with nothing before it or nothing
+    /// inside is left as part of the word.
+    fn extract_hints(tokens: &mut [Token], options: &ParseOptions) -> HashMap<usize, String> {
+        let mut hints = HashMap::new();
+        let Some((open, close)) = &options.hint_markers else {
+            return hints;
+        };
+
+        for (index, token) in tokens.iter_mut().enumerate() {
+            let Token::Word(word) = token else {
+                continue;
+            };
+            let Some((start, hint)) = word
+                .strip_suffix(close.as_str())
+                .and_then(|rest| rest.rsplit_once(open.as_str()))
+            else {
+                continue;
+            };
+            if start.is_empty() || hint.is_empty() {
+                continue;
+            }
+
+            hints.insert(index, hint.to_string());
+            word.truncate(start.len());
+        }
+        hints
+    }
+
+    /// A copy with the words in [`WordList::originals`] spelled as written,
+    /// for passing an entry through uncorrected.
+    pub fn as_written(&self) -> WordList {
+        let mut written = self.clone();
+        for (&index, original) in &self.originals {
+            written.tokens[index] = Token::Word(original.clone());
+        }
+        written.originals.clear();
+        written
+    }
+
+    /// Collapses every run of consecutive separators into a single one,
+    /// including the run after the ID. Multi-character delimiters are kept.
+    pub fn merge_separators(&mut self, keep: SeparatorMerge) {
+        let kept = match keep {
+            SeparatorMerge::First => self.id_separator.chars().next(),
+            SeparatorMerge::Last => self.id_separator.chars().last(),
+        };
+        if let Some(kept) = kept {
+            self.id_separator = kept.to_string();
+        }
+
+        let mut merged: Vec<Token> = Vec::with_capacity(self.tokens.len());
+
+        for token in self.tokens.drain(..) {
+            match (merged.last_mut(), &token) {
+                (Some(Token::Separator(previous)), Token::Separator(c)) => {
+                    if keep == SeparatorMerge::Last {
+                        *previous = *c;
+                    }
+                }
+                _ => merged.push(token),
+            }
+        }
+
+        self.tokens = merged;
+    }
+
+    /// IDs used by more than one entry, each with the line numbers of all its
+    /// entries, in order of first appearance.
+    pub fn duplicate_ids(word_lists: &[WordList]) -> Vec<(&str, Vec<usize>)> {
+        let mut lines: Vec<(&str, Vec<usize>)> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+
+        for word_list in word_lists {
+            let position = *positions.entry(&word_list.id).or_insert_with(|| {
+                lines.push((&word_list.id, Vec::new()));
+                lines.len() - 1
+            });
+            lines[position].1.push(word_list.line_number);
+        }
+
+        lines.retain(|(_, line_numbers)| line_numbers.len() > 1);
+        lines
+    }
+
+    /// Removes the separators, delimiters and whitespace after the last
+    /// word, as in "0001 and/or/ " -> "0001 and/or". Separators between
+    /// words are kept.
+    pub fn trim_trailing_separators(&mut self) {
+        self.trailing.clear();
+        while matches!(
+            self.tokens.last(),
+            Some(Token::Separator(_) | Token::Delimiter(_))
+        ) {
+            self.tokens.pop();
+        }
+    }
+
+    /// Removes words that repeat the word before them, ignoring case, as in
+    /// "the the quick" -> "the quick". The separators between the pair go
+    /// with the duplicate; the first spelling is kept. Returns how many words
+    /// were removed.
+    ///
+    /// This is a heuristic for OCR and typing slips: some repetitions are
+    /// intended ("that that", "had had"), and those are removed as well.
+    pub fn dedup_words(&mut self) -> usize {
+        let mut kept: Vec<Token> = Vec::with_capacity(self.tokens.len());
+        // Index in `kept` of the last word, which separators may follow.
+        let mut last_word: Option<usize> = None;
+        let mut removed = 0;
+
+        for token in self.tokens.drain(..) {
+            if let Token::Word(word) = &token {
+                if let Some(index) = last_word
+                    && let Token::Word(previous) = &kept[index]
+                    && previous.to_lowercase() == word.to_lowercase()
+                {
+                    kept.truncate(index + 1);
+                    removed += 1;
+                    continue;
+                }
+                last_word = Some(kept.len());
+            }
+            kept.push(token);
+        }
+
+        self.tokens = kept;
+        removed
+    }
+
+    pub fn parse_tokens(words_part: &str, options: &ParseOptions) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut current_word = String::new();
+
+        let mut rest = words_part;
+
+        while let Some(char) = rest.chars().next() {
+            if options.preserve_tags
+                && let Some(tag) = leading_tag(rest)
+            {
+                if !current_word.is_empty() {
+                    tokens.push(Token::Word(current_word));
+                    current_word = String::new();
+                }
+                tokens.push(Token::Delimiter(tag.to_string()));
+                rest = &rest[tag.len()..];
+                continue;
+            }
+            if let Some(delimiter) = options
+                .delimiters
+                .iter()
+                .filter(|delimiter| !delimiter.is_empty() && rest.starts_with(delimiter.as_str()))
+                .max_by_key(|delimiter| delimiter.len())
+            {
+                if !current_word.is_empty() {
+                    tokens.push(Token::Word(current_word));
+                    current_word = String::new();
+                }
+                tokens.push(Token::Delimiter(delimiter.clone()));
+                rest = &rest[delimiter.len()..];
+                continue;
+            }
+            rest = &rest[char.len_utf8()..];
+
+            if options.control_chars == ControlCharPolicy::Strip
+                && char.is_control()
+                && !options.splits_on(char)
+            {
+                continue;
+            }
+
+            if options.is_separator(char) {
+                if !current_word.is_empty() {
+                    tokens.push(Token::Word(current_word));
+                    current_word = String::new();
+                }
+                tokens.push(Token::Separator(char));
+            } else {
+                current_word.push(char);
+            }
+        }
+
+        if !current_word.is_empty() {
+            tokens.push(Token::Word(current_word));
+        }
+
+        debug_assert!(
+            tokens
+                .iter()
+                .all(|token| !matches!(token, Token::Word(word) if word.is_empty())),
+            "parse_tokens produced an empty word"
+        );
+        tokens
+    }
+}
+
+/// Splits a raw input line into the whitespace before the entry, the entry
+/// and the whitespace after it. Trailing whitespace right after the last
+/// word stays in the entry while it is made of separators, so it is
+/// tokenized like any other separator. `None` for blank lines.
+pub(crate) fn split_line<'a>(
+    line: &'a str,
+    options: &ParseOptions,
+) -> Option<(&'a str, &'a str, &'a str)> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let start = line.len() - line.trim_start().len();
+    let padding = &line[start + trimmed.len()..];
+    let kept = padding.len()
+        - padding
+            .trim_start_matches(|c| options.is_separator(c))
+            .len();
+    let end = start + trimmed.len() + kept;
+    Some((&line[..start], &line[start..end], &line[end..]))
+}
+
+/// The tag at the start of `text`: `<`, an optional `/`, a letter, then
+/// anything up to the first `>` on the same line with no `<` in between.
+fn leading_tag(text: &str) -> Option<&str> {
+    let name = text.strip_prefix('<')?;
+    let name = name.strip_prefix('/').unwrap_or(name);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let end = 1 + text[1..].find(['>', '<', '\n'])?;
+    (text.as_bytes()[end] == b'>').then(|| &text[..=end])
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word),
+            Token::Separator(c) => write!(f, "{}", c),
+            Token::Delimiter(delimiter) => write!(f, "{}", delimiter),
+        }
+    }
+}
+
+impl std::fmt::Display for WordList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.leading, self.id, self.id_separator)?;
+        for token in &self.tokens {
+            write!(f, "{}", token)?;
+        }
+        write!(f, "{}", self.trailing)
+    }
+}
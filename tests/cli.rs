@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Enough words that the binary corrects instead of only checking.
+const VOCABULARY: &str = "a\nand\nin\nis\nit\nof\nthe\ntheir\nthere\nto\nwas\n";
+
+/// A fresh directory named `name` laid out the way the binary expects, with
+/// `words` as the input. Returns the directory.
+fn project(name: &str, words: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("problem")).unwrap();
+    fs::write(dir.join("problem/vocabulary.txt"), VOCABULARY).unwrap();
+    fs::write(dir.join("problem/words.txt"), words).unwrap();
+    dir
+}
+
+/// The binary, run from `dir`.
+fn binary(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_homework_03"));
+    command.current_dir(dir);
+    command
+}
+
+mod in_place {
+    use super::{binary, project};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Output;
+
+    fn run(name: &str, words: &str, args: &[&str]) -> (PathBuf, Output) {
+        let dir = project(name, words);
+        let output = binary(&dir).args(args).output().unwrap();
+        (dir.join("problem/words.txt"), output)
+    }
+
+    #[test]
+    fn lines_that_are_not_entries_are_kept() {
+        let (path, output) = run(
+            "in_place_cli",
+            "---\nmax_distance: 1\n---\n0001 thes\n\nnot an entry\n0002 tha\n",
+            &["--in-place", "--lenient"],
+        );
+
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\nmax_distance: 1\n---\n0001 the\n\nnot an entry\n0002 the\n"
+        );
+    }
+
+    #[test]
+    fn another_output_is_refused() {
+        let original = "0001 thes\n";
+        let (path, output) = run(
+            "in_place_cli_output",
+            original,
+            &["--in-place", "--output", "other.txt"],
+        );
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("--output"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn distance_annotations_are_refused() {
+        let original = "0001 thes\n";
+        let (path, output) = run(
+            "in_place_cli_annotate",
+            original,
+            &["--in-place", "--annotate-distance"],
+        );
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("--annotate-distance"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn the_command_line_distance_limit_wins_over_front_matter() {
+        let (path, output) = run(
+            "in_place_cli_front_matter",
+            "---\nmax_distance: 0\n---\n0001 thes\n",
+            &["--in-place", "--max-distance", "1"],
+        );
+
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\nmax_distance: 0\n---\n0001 the\n"
+        );
+    }
+}
+
+mod learned {
+    use super::{binary, project};
+    use std::fs;
+    use std::io::Write;
+    use std::process::Stdio;
+
+    #[test]
+    fn only_what_the_user_confirms_is_learned() {
+        let dir = project("learned_cli", "0001 thes theer\n0002 thes\n");
+
+        let mut child = binary(&dir)
+            .args(["--learned", "learned.txt", "--learn"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // Declines "thes -> the", accepts "theer -> their".
+        child.stdin.take().unwrap().write_all(b"n\ny\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.matches("Learn '").count(), 2, "{}", stdout);
+        assert_eq!(
+            fs::read_to_string(dir.join("learned.txt")).unwrap(),
+            "theer their\n"
+        );
+    }
+
+    #[test]
+    fn nothing_is_learned_without_answers() {
+        let dir = project("learned_cli_eof", "0001 thes theer\n");
+
+        let status = binary(&dir)
+            .args(["--learned", "learned.txt", "--learn"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        assert_eq!(fs::read_to_string(dir.join("learned.txt")).unwrap(), "");
+    }
+}
@@ -0,0 +1,6 @@
+use homework_03::SpellChecker;
+
+/// A checker over exactly `words` with the default settings.
+pub fn checker(words: &[&str]) -> SpellChecker {
+    SpellChecker::from_words(words.iter().map(|word| word.to_string())).unwrap()
+}
@@ -0,0 +1,1770 @@
+mod common;
+
+mod annotate_distance {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    fn checker() -> SpellChecker {
+        common::checker(&["apple", "banana", "cherry", "the"]).with_annotate_distance(true)
+    }
+
+    fn corrected(checker: &SpellChecker, line: &str) -> String {
+        let word_list = WordList::parse_content(line, &ParseOptions::default())
+            .unwrap()
+            .remove(0);
+        checker.correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn changed_words_carry_their_distance() {
+        assert_eq!(
+            corrected(&checker(), "0001 aple bananxx"),
+            "0001 apple[1] banana[2]"
+        );
+    }
+
+    #[test]
+    fn unchanged_words_and_separators_are_printed_normally() {
+        let checker = checker();
+        assert_eq!(
+            corrected(&checker, "0001 apple/banana  cherry"),
+            "0001 apple/banana  cherry"
+        );
+        assert_eq!(
+            corrected(&checker, "0001 apple/teh  cherry"),
+            "0001 apple/the[2]  cherry"
+        );
+    }
+
+    #[test]
+    fn annotation_is_off_by_default() {
+        let checker = common::checker(&["apple", "banana"]);
+        assert_eq!(corrected(&checker, "0001 aple"), "0001 apple");
+    }
+}
+
+mod audit {
+    use crate::common;
+    use homework_03::{ParseOptions, Strategy, WordList, write_audit};
+    use std::sync::atomic::AtomicBool;
+
+    const WORDS: &[&str] = &["apple", "cat", "the"];
+
+    #[test]
+    fn one_record_per_word_token() {
+        let checker = common::checker(WORDS);
+        let word_list =
+            WordList::parse_line(7, "0001 aple cat 42 the", &ParseOptions::default()).unwrap();
+
+        let decisions = checker.decide_word_list(&word_list);
+        let mut out = Vec::new();
+        write_audit(&mut out, &word_list, &decisions).unwrap();
+        let log = String::from_utf8(out).unwrap();
+
+        let records: Vec<&str> = log.lines().collect();
+        assert_eq!(records.len(), 4);
+        assert!(records.iter().all(|record| {
+            record.starts_with("{\"id\":\"0001\",\"line\":7,") && record.ends_with('}')
+        }));
+        assert!(records[0].contains("\"input\":\"aple\",\"output\":\"apple\",\"distance\":1"));
+        assert!(records[0].contains("\"strategy\":\"nearest\""));
+        assert!(records[1].contains("\"strategy\":\"known\""));
+        assert!(records[2].contains("\"strategy\":\"skipped\""));
+    }
+
+    #[test]
+    fn decisions_match_correct_word() {
+        let checker = common::checker(WORDS);
+        for word in ["aple", "cat", "xyzzy", "42", ""] {
+            assert_eq!(checker.decide(word).output, checker.correct_word(word));
+        }
+    }
+
+    #[test]
+    fn only_searches_count_candidates() {
+        let checker = common::checker(WORDS);
+
+        let known = checker.decide("cat");
+        assert_eq!(known.strategy, Strategy::Known);
+        assert_eq!((known.distance, known.candidates), (0, 0));
+
+        let corrected = checker.decide("aple");
+        assert_eq!(corrected.strategy, Strategy::Nearest);
+        assert!(corrected.candidates > 0);
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let checker = common::checker(WORDS);
+        let record = checker.decide("a\"b").to_json("id\\1", 1);
+        assert!(record.contains("\"input\":\"a\\\"b\""));
+        assert!(record.contains("\"id\":\"id\\\\1\""));
+    }
+
+    #[test]
+    fn decisions_follow_the_line_level_rules() {
+        let checker = common::checker(&["and", "the"]).with_max_corrections_per_line(1);
+        let word_list = WordList::parse_line(1, "0001 teh adn", &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            checker.correct_word_list(&word_list).to_string(),
+            "0001 the adn"
+        );
+        let decisions = checker.decide_word_list(&word_list);
+        assert_eq!(
+            decisions
+                .iter()
+                .map(|d| (d.input.as_str(), d.output.as_str(), d.strategy))
+                .collect::<Vec<_>>(),
+            [
+                ("teh", "the", Strategy::Nearest),
+                ("adn", "adn", Strategy::Limited)
+            ]
+        );
+    }
+
+    #[test]
+    fn audited_runs_record_what_the_output_got_without_extra_searches() {
+        let content = "0001 aple cat\n0002 teh\n0003 cat\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+
+        let plain = common::checker(WORDS);
+        let expected = plain.correct_all_tracked(&word_lists, 2, |_| true, &AtomicBool::new(false));
+
+        let audited = common::checker(WORDS);
+        let mut records = Vec::new();
+        let run = audited.correct_all_audited(
+            &word_lists,
+            2,
+            |word_list| word_list.id != "0003",
+            &AtomicBool::new(false),
+            |word_list, decisions| {
+                for decision in decisions {
+                    records.push((word_list.id.clone(), decision.output.clone()));
+                }
+            },
+        );
+
+        assert_eq!(
+            records,
+            [
+                ("0001".to_string(), "apple".to_string()),
+                ("0001".to_string(), "cat".to_string()),
+                ("0002".to_string(), "the".to_string()),
+            ]
+        );
+        assert_eq!(
+            run.results[0].0.to_string(),
+            expected.results[0].0.to_string()
+        );
+        assert_eq!(
+            audited.comparison_stats().searches,
+            plain.comparison_stats().searches
+        );
+    }
+
+    #[test]
+    fn a_cancelled_run_records_nothing() {
+        let word_lists = WordList::parse_content("0001 aple\n", &ParseOptions::default()).unwrap();
+        let mut records = 0;
+
+        common::checker(WORDS).correct_all_audited(
+            &word_lists,
+            1,
+            |_| true,
+            &AtomicBool::new(true),
+            |_, decisions| records += decisions.len(),
+        );
+
+        assert_eq!(records, 0);
+    }
+}
+
+mod cache {
+    use crate::common;
+    use homework_03::SpellChecker;
+
+    fn checker() -> SpellChecker {
+        common::checker(&["the", "café", "naïve"])
+            .with_ignore_case(true)
+            .with_cache(true)
+    }
+
+    #[test]
+    fn case_variants_share_one_cached_correction() {
+        let checker = checker();
+
+        assert_eq!(checker.correct_word("teh"), "the");
+        assert_eq!(checker.cache_hits(), 0);
+        assert_eq!(checker.correct_word("Teh"), "The");
+        assert_eq!(checker.correct_word("TEH"), "THE");
+        assert_eq!(checker.cache_hits(), 2);
+    }
+
+    #[test]
+    fn composed_and_decomposed_accents_share_one_cached_correction() {
+        let checker = checker();
+
+        // "cafè" with a precomposed è, then with e and a combining grave.
+        assert_eq!(checker.correct_word("caf\u{e8}"), "café");
+        assert_eq!(checker.correct_word("cafe\u{300}"), "café");
+        assert_eq!(checker.correct_word("CAFE\u{300}"), "CAFÉ");
+        assert_eq!(checker.cache_hits(), 2);
+    }
+
+    #[test]
+    fn a_decomposed_spelling_of_an_entry_is_known() {
+        let checker = checker();
+
+        assert_eq!(checker.correct_word("cafe\u{301}"), "cafe\u{301}");
+        assert_eq!(checker.correct_word("Nai\u{308}ve"), "Nai\u{308}ve");
+        assert!(checker.contains_word("na\u{ef}ve"));
+        assert_eq!(checker.cache_hits(), 0);
+    }
+
+    #[test]
+    fn case_sensitive_lookups_compose_accents_too() {
+        let checker = common::checker(&["café", "naïve"]).with_cache(true);
+
+        assert_eq!(checker.correct_word("cafe\u{301}"), "cafe\u{301}");
+        assert_eq!(checker.correct_word("nai\u{308}ev"), "naïve");
+        assert_eq!(checker.correct_word("naïev"), "naïve");
+        assert_eq!(checker.cache_hits(), 1);
+    }
+
+    #[test]
+    fn an_uncorrectable_word_keeps_its_accents_as_written() {
+        let checker = common::checker(&["café"])
+            .with_max_distance(1)
+            .with_cache(true);
+        assert_eq!(checker.correct_word("xyze\u{301}"), "xyze\u{301}");
+    }
+}
+
+mod collapse_repeats {
+    use crate::common;
+    use homework_03::{SpellChecker, Strategy};
+
+    fn checker(collapse_repeats: bool) -> SpellChecker {
+        common::checker(&["col", "committee", "cool", "hey", "so", "soon", "yes"])
+            .with_collapse_repeats(collapse_repeats)
+    }
+
+    #[test]
+    fn elongated_words_collapse_to_their_base() {
+        let checker = checker(true);
+        assert_eq!(checker.correct_word("soooo"), "so");
+        assert_eq!(checker.correct_word("heyyy"), "hey");
+        assert_eq!(checker.decide("soooo").strategy, Strategy::Collapsed);
+    }
+
+    #[test]
+    fn the_form_keeping_a_double_letter_wins() {
+        assert_eq!(checker(true).correct_word("coool"), "cool");
+    }
+
+    #[test]
+    fn every_run_is_collapsed() {
+        assert_eq!(checker(true).correct_word("yyyeeesss"), "yes");
+    }
+
+    #[test]
+    fn double_letters_are_left_alone() {
+        let checker = checker(true);
+        assert_eq!(checker.correct_word("cool"), "cool");
+        assert_eq!(checker.correct_word("comittee"), "committee");
+    }
+
+    #[test]
+    fn it_is_opt_in() {
+        assert_ne!(checker(false).correct_word("soooo"), "so");
+    }
+
+    #[test]
+    fn case_is_kept() {
+        let checker = checker(true).with_ignore_case(true);
+        assert_eq!(checker.correct_word("Soooo"), "So");
+    }
+}
+
+mod context_distance {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    fn checker(context_distance: bool) -> SpellChecker {
+        common::checker(&[
+            "brown", "dog", "fox", "jumps", "lazy", "over", "quick", "the",
+        ])
+        .with_max_distance(1)
+        .with_context_distance(context_distance)
+    }
+
+    fn correct(checker: &SpellChecker, line: &str) -> String {
+        let word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+        checker.correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn a_clean_line_allows_one_more_edit() {
+        // "qiuck" is two edits from "quick".
+        let line = "0001 the qiuck brown fox";
+        assert_eq!(correct(&checker(false), line), line);
+        assert_eq!(correct(&checker(true), line), "0001 the quick brown fox");
+    }
+
+    #[test]
+    fn a_mostly_garbage_line_is_corrected_more_conservatively() {
+        let line = "0001 xq zzkv lazzy wrrp";
+        assert_eq!(correct(&checker(false), line), "0001 xq zzkv lazy wrrp");
+        assert_eq!(correct(&checker(true), line), line);
+    }
+
+    #[test]
+    fn a_mixed_line_keeps_the_usual_limit() {
+        let line = "0001 the lazzy zzkv dog";
+        let expected = "0001 the lazy zzkv dog";
+        assert_eq!(correct(&checker(false), line), expected);
+        assert_eq!(correct(&checker(true), line), expected);
+    }
+
+    #[test]
+    fn single_words_are_not_affected() {
+        assert_eq!(correct(&checker(true), "0001 qiuck"), "0001 qiuck");
+        assert_eq!(checker(true).correct_word("qiuck"), "qiuck");
+    }
+}
+
+mod correct_all {
+    use crate::common;
+    use homework_03::{ParseOptions, WordList};
+    use std::sync::atomic::AtomicBool;
+
+    const WORDS: &[&str] = &["apple", "banana", "cherry", "date", "elder"];
+
+    fn word_lists() -> Vec<WordList> {
+        let content =
+            "0001 aple banana\n0002 chery/dat\n0003 elder\n0004 bananna  apple\n0005 eldr\n";
+        WordList::parse_content(content, &ParseOptions::default()).unwrap()
+    }
+
+    fn render(word_lists: &[WordList]) -> Vec<String> {
+        word_lists.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn matches_correcting_line_by_line() {
+        let checker = common::checker(WORDS);
+        let word_lists = word_lists();
+
+        let expected: Vec<WordList> = word_lists
+            .iter()
+            .map(|word_list| checker.correct_word_list(word_list))
+            .collect();
+
+        assert_eq!(render(&checker.correct_all(&word_lists)), render(&expected));
+        assert_eq!(
+            render(&expected),
+            [
+                "0001 apple banana",
+                "0002 cherry/date",
+                "0003 elder",
+                "0004 banana  apple",
+                "0005 elder"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_number_of_jobs_does_not_change_the_result() {
+        let checker = common::checker(WORDS);
+        let word_lists = word_lists();
+        let serial = checker
+            .correct_all_tracked(&word_lists, 1, |_| true, &AtomicBool::new(false))
+            .results;
+
+        for jobs in [2, 3, 8] {
+            let parallel = checker
+                .correct_all_tracked(&word_lists, jobs, |_| true, &AtomicBool::new(false))
+                .results;
+            assert_eq!(parallel.len(), serial.len());
+            for ((a, a_changes), (b, b_changes)) in parallel.iter().zip(&serial) {
+                assert_eq!(a.to_string(), b.to_string());
+                assert_eq!(a_changes, b_changes);
+            }
+        }
+    }
+
+    #[test]
+    fn unselected_lines_pass_through() {
+        let checker = common::checker(WORDS);
+        let results = checker
+            .correct_all_tracked(
+                &word_lists(),
+                2,
+                |word_list| word_list.id != "0002",
+                &AtomicBool::new(false),
+            )
+            .results;
+
+        assert_eq!(results[1].0.to_string(), "0002 chery/dat");
+        assert!(results[1].1.is_empty());
+        assert_eq!(results[0].0.to_string(), "0001 apple banana");
+    }
+}
+
+mod correct_into {
+    use crate::common;
+
+    const WORDS: &[&str] = &["apple", "banana", "cherry", "Paris"];
+
+    const INPUTS: [&str; 7] = ["apple", "aple", "bananna", "", "chery", "paris", "PARIS"];
+
+    #[test]
+    fn buffer_matches_correct_word() {
+        let checker = common::checker(WORDS);
+        let mut buffer = String::from("left over from an earlier call");
+
+        for word in INPUTS {
+            checker.correct_word_into(word, &mut buffer);
+            assert_eq!(buffer, checker.correct_word(word), "word {:?}", word);
+        }
+    }
+
+    #[test]
+    fn buffer_matches_correct_word_ignoring_case() {
+        let checker = common::checker(WORDS).with_ignore_case(true);
+        let mut buffer = String::new();
+
+        for word in INPUTS {
+            checker.correct_word_into(word, &mut buffer);
+            assert_eq!(buffer, checker.correct_word(word), "word {:?}", word);
+        }
+    }
+}
+
+mod cross_check {
+    use crate::common;
+    use homework_03::{CandidateSource, Disagreement, ParseOptions, WordList};
+
+    /// Drops every entry longer than the word, a pruning bug the cross-check
+    /// should catch.
+    struct ShorterOnly;
+
+    impl CandidateSource for ShorterOnly {
+        fn candidates(
+            &self,
+            dictionary: &[String],
+            word: &str,
+            _max_distance: usize,
+        ) -> Vec<usize> {
+            (0..dictionary.len())
+                .filter(|&i| dictionary[i].chars().count() <= word.chars().count())
+                .collect()
+        }
+    }
+
+    const WORDS: &[&str] = &["apple", "apples", "cat"];
+
+    fn word_list() -> WordList {
+        WordList::parse_line(1, "0001 aple cat applez", &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn the_linear_scan_agrees_with_the_reference() {
+        assert!(
+            common::checker(WORDS)
+                .cross_check_word_list(&word_list())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn a_pruning_source_is_caught() {
+        let checker = common::checker(WORDS).with_candidate_source(Box::new(ShorterOnly));
+        assert_eq!(
+            checker.cross_check_word_list(&word_list()),
+            vec![Disagreement {
+                word: "aple".to_string(),
+                fast: "cat".to_string(),
+                fast_distance: 4,
+                reference: "apple".to_string(),
+                reference_distance: 1,
+            }]
+        );
+    }
+}
+
+mod diff {
+    use crate::common;
+    use homework_03::{LineDiff, ParseOptions, Token, WordList};
+
+    const WORDS: &[&str] = &["apple", "banana", "cherry", "date"];
+
+    fn entry(line: &str) -> WordList {
+        WordList::parse_content(line, &ParseOptions::default())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn a_correct_line_has_an_empty_diff() {
+        let diff = common::checker(WORDS).diff_word_list(&entry("0001 apple banana/cherry"));
+
+        assert!(diff.is_empty());
+        assert_eq!(
+            diff,
+            LineDiff {
+                id: "0001".to_string(),
+                changes: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn a_multi_change_line_lists_each_changed_word_with_its_token_index() {
+        let word_list = entry("0002 aple banana/chery  dat");
+        let diff = common::checker(WORDS).diff_word_list(&word_list);
+
+        assert_eq!(diff.id, "0002");
+        assert_eq!(
+            diff.changes,
+            [
+                (0, "aple".to_string(), "apple".to_string()),
+                (4, "chery".to_string(), "cherry".to_string()),
+                (7, "dat".to_string(), "date".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn applying_the_patch_reproduces_the_corrected_line() {
+        let checker = common::checker(WORDS);
+        let mut word_list = entry("0003 aple/banan date chery");
+        let corrected = checker.correct_word_list(&word_list).to_string();
+
+        for (index, _, new) in checker.diff_word_list(&word_list).changes {
+            word_list.tokens[index] = Token::Word(new);
+        }
+        assert_eq!(word_list.to_string(), corrected);
+    }
+}
+
+mod drop_unknown {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    fn checker() -> SpellChecker {
+        common::checker(&["apple", "pie", "tart"])
+            .with_max_distance(1)
+            .with_drop_unknown(true)
+    }
+
+    fn correct(line: &str) -> String {
+        let word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+        checker().correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn an_unknown_word_takes_one_separator_with_it() {
+        assert_eq!(correct("0001 apple xyzzy pie"), "0001 apple pie");
+    }
+
+    #[test]
+    fn unknown_words_at_either_end_leave_no_dangling_separator() {
+        assert_eq!(correct("0001 xyzzy apple"), "0001 apple");
+        assert_eq!(correct("0001 apple xyzzy"), "0001 apple");
+    }
+
+    #[test]
+    fn consecutive_unknown_words_collapse_to_one_separator() {
+        assert_eq!(correct("0001 apple xyzzy qwert pie"), "0001 apple pie");
+        assert_eq!(correct("0001 apple/xyzzy/tart"), "0001 apple/tart");
+    }
+
+    #[test]
+    fn words_within_the_threshold_are_corrected_not_dropped() {
+        assert_eq!(correct("0001 aple xyzzy pie"), "0001 apple pie");
+    }
+
+    #[test]
+    fn the_output_parses_back_to_the_same_entry() {
+        let written = correct("0001 xyzzy apple  qwert pie");
+        let reparsed = WordList::parse_line(1, &written, &ParseOptions::default()).unwrap();
+        assert_eq!(reparsed.to_string(), written);
+        assert!(!written.ends_with(' '));
+    }
+
+    #[test]
+    fn without_the_mode_unknown_words_are_kept() {
+        let checker = common::checker(&["apple", "pie"]).with_max_distance(1);
+        let word_list =
+            WordList::parse_line(1, "0001 apple xyzzy", &ParseOptions::default()).unwrap();
+        assert_eq!(
+            checker.correct_word_list(&word_list).to_string(),
+            "0001 apple xyzzy"
+        );
+    }
+}
+
+mod edit_costs {
+    use crate::common;
+    use homework_03::{EditCosts, SpellChecker, weighted_levenshtein};
+
+    fn checker(insertion: usize, deletion: usize) -> SpellChecker {
+        common::checker(&["at", "cart", "cut"]).with_edit_costs(EditCosts {
+            insertion,
+            deletion,
+            substitution: 2,
+        })
+    }
+
+    #[test]
+    fn default_costs_match_plain_levenshtein() {
+        let costs = EditCosts::default();
+        for (a, b) in [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("flaw", "lawn"),
+            ("same", "same"),
+        ] {
+            assert_eq!(
+                weighted_levenshtein(a, b, &costs),
+                strsim::levenshtein(a, b)
+            );
+        }
+    }
+
+    #[test]
+    fn costs_apply_in_the_direction_of_the_edit() {
+        let costs = EditCosts {
+            insertion: 1,
+            deletion: 5,
+            substitution: 9,
+        };
+        assert_eq!(weighted_levenshtein("cat", "cart", &costs), 1);
+        assert_eq!(weighted_levenshtein("cart", "cat", &costs), 5);
+        assert_eq!(weighted_levenshtein("cat", "cut", &costs), 6);
+    }
+
+    #[test]
+    fn cheap_insertions_prefer_the_longer_candidate() {
+        let checker = checker(1, 3);
+        assert_eq!(checker.correct_word("cat"), "cart");
+        let ranked: Vec<_> = checker
+            .correct_word_candidates("cat", 3)
+            .into_iter()
+            .map(|suggestion| suggestion.into_word())
+            .collect();
+        assert_eq!(ranked, ["cart", "cut", "at"]);
+    }
+
+    #[test]
+    fn cheap_deletions_prefer_the_shorter_candidate() {
+        let checker = checker(3, 1);
+        assert_eq!(checker.correct_word("cat"), "at");
+        assert_eq!(checker.edit_distance("cat", "cart"), 3);
+    }
+
+    #[test]
+    fn free_edits_do_not_stop_the_search_at_distance_one() {
+        let checker = common::checker(&["at", "bat"]).with_edit_costs(EditCosts {
+            insertion: 0,
+            deletion: 1,
+            substitution: 1,
+        });
+
+        // "at" is one substitution away and comes first; "bat" is a free insertion.
+        assert_eq!(checker.correct_word("bt"), "bat");
+    }
+}
+
+mod exhaustive {
+    use crate::common;
+    use homework_03::{SpellChecker, TieBreak};
+    use std::collections::HashMap;
+
+    const WORDS: &[&str] = &["bat", "cart", "cat", "coat", "cost", "post", "xylophone"];
+
+    fn capped(exhaustive: bool) -> SpellChecker {
+        common::checker(WORDS)
+            .with_max_candidates(1)
+            .with_exhaustive(exhaustive)
+    }
+
+    #[test]
+    fn exhaustive_search_ignores_the_candidate_cap() {
+        // The cap keeps only "xylophone", the one entry sharing the first letter.
+        assert_eq!(capped(false).correct_word("xat"), "xylophone");
+        assert_eq!(capped(true).correct_word("xat"), "bat");
+    }
+
+    #[test]
+    fn exhaustive_search_is_never_farther_than_the_fast_path() {
+        let (fast, exhaustive) = (capped(false), capped(true));
+
+        for word in ["xat", "caat", "cosst", "pst", "cot", "coast", "xyz"] {
+            let fast_pick = fast.correct_word(word);
+            let exhaustive_pick = exhaustive.correct_word(word);
+            assert!(
+                fast.edit_distance(word, &exhaustive_pick) <= fast.edit_distance(word, &fast_pick),
+                "'{}': exhaustive '{}' vs fast '{}'",
+                word,
+                exhaustive_pick,
+                fast_pick
+            );
+        }
+    }
+
+    #[test]
+    fn without_pruning_both_paths_agree() {
+        let fast = common::checker(WORDS);
+        let exhaustive = common::checker(WORDS).with_exhaustive(true);
+
+        for word in ["xat", "caat", "cosst", "pst", "cot", "coast"] {
+            assert_eq!(fast.correct_word(word), exhaustive.correct_word(word));
+        }
+    }
+
+    #[test]
+    fn exhaustive_search_keeps_the_configured_tie_break() {
+        let frequencies = HashMap::from([("cat".to_string(), 50)]);
+        let pick = |tie_break| {
+            common::checker(WORDS)
+                .with_frequencies(frequencies.clone())
+                .with_tie_break(tie_break)
+                .with_exhaustive(true)
+                .correct_word("xat")
+        };
+
+        assert_eq!(pick(TieBreak::Alphabetical), "bat");
+        assert_eq!(pick(TieBreak::ShortestFirst), "bat");
+    }
+}
+
+mod forbidden {
+    use crate::common;
+    use homework_03::{SpellChecker, SpellError};
+    use std::collections::HashSet;
+
+    fn forbid(pairs: &[(&str, &str)]) -> HashSet<(String, String)> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn a_forbidden_substitution_falls_back_to_the_next_best_candidate() {
+        let checker = common::checker(&["the", "their", "there"]);
+        assert_eq!(checker.correct_word("ther"), "the");
+
+        let checker = checker.with_forbidden(forbid(&[("ther", "the")]));
+        assert_eq!(checker.correct_word("ther"), "their");
+    }
+
+    #[test]
+    fn the_word_is_kept_when_every_candidate_is_forbidden() {
+        let checker = common::checker(&["the", "their"])
+            .with_forbidden(forbid(&[("ther", "the"), ("ther", "their")]));
+        assert_eq!(checker.correct_word("ther"), "ther");
+    }
+
+    #[test]
+    fn a_forbidden_pair_only_applies_to_its_input_word() {
+        let checker = common::checker(&["the", "their"]).with_forbidden(forbid(&[("ther", "the")]));
+        assert_eq!(checker.correct_word("teh"), "the");
+    }
+
+    #[test]
+    fn known_words_are_unaffected() {
+        let checker = common::checker(&["the"]).with_forbidden(forbid(&[("the", "the")]));
+        assert_eq!(checker.correct_word("the"), "the");
+    }
+
+    #[test]
+    fn parses_one_pair_per_line() {
+        let parsed = SpellChecker::parse_forbidden("ther the\n\n  teh  tea \n").unwrap();
+        assert_eq!(parsed, forbid(&[("ther", "the"), ("teh", "tea")]));
+    }
+
+    #[test]
+    fn rejects_lines_without_exactly_two_words() {
+        for content in ["ther\n", "ther the their\n"] {
+            assert!(matches!(
+                SpellChecker::parse_forbidden(content),
+                Err(SpellError::InvalidLine { line: 1, .. })
+            ));
+        }
+    }
+}
+
+mod forced {
+    use crate::common;
+    use homework_03::{Dictionary, SpellChecker};
+    use std::collections::HashMap;
+
+    fn forced(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn a_forced_correction_applies_regardless_of_distance() {
+        let checker = common::checker(&["the", "and"])
+            .with_max_distance(1)
+            .with_forced(forced(&[("adn", "and"), ("thx", "thanks")]));
+
+        assert_eq!(checker.correct_word("adn"), "and");
+        // Not even a dictionary word, and far from every entry.
+        assert_eq!(checker.correct_word("thx"), "thanks");
+    }
+
+    #[test]
+    fn a_forced_correction_overrides_a_dictionary_word() {
+        let checker = common::checker(&["teh", "the"]).with_forced(forced(&[("teh", "the")]));
+        assert_eq!(checker.correct_word("teh"), "the");
+    }
+
+    #[test]
+    fn forced_corrections_take_precedence_over_the_ignore_heuristics() {
+        let checker = common::checker(&["two", "http"])
+            .with_skip_acronyms(true)
+            .with_forced(forced(&[("2", "two"), ("HTPP", "HTTP")]));
+
+        assert_eq!(checker.correct_word("2"), "two");
+        assert_eq!(checker.correct_word("HTPP"), "HTTP");
+        // Ignored tokens without a forced correction stay as written.
+        assert_eq!(checker.correct_word("3"), "3");
+        assert_eq!(checker.correct_word("HTTPS"), "HTTPS");
+    }
+
+    #[test]
+    fn forced_corrections_take_precedence_over_accepted_words() {
+        let accepted =
+            Dictionary::from_words(["colour".to_string(), "favour".to_string()]).unwrap();
+        let checker = common::checker(&["color", "favor"])
+            .with_accepted(accepted)
+            .with_forced(forced(&[("colour", "color")]));
+
+        assert_eq!(checker.correct_word("colour"), "color");
+        assert_eq!(checker.correct_word("favour"), "favour");
+    }
+
+    #[test]
+    fn forced_corrections_take_precedence_over_learned_ones() {
+        let checker = common::checker(&["the", "then"])
+            .with_learned(forced(&[("teh", "then")]))
+            .with_forced(forced(&[("teh", "the")]));
+        assert_eq!(checker.correct_word("teh"), "the");
+    }
+
+    #[test]
+    fn parses_one_pair_per_line() {
+        let parsed = SpellChecker::parse_forced("teh the\nadn  and\n").unwrap();
+        assert_eq!(parsed, forced(&[("teh", "the"), ("adn", "and")]));
+        assert!(SpellChecker::parse_forced("teh\n").is_err());
+    }
+
+    #[test]
+    fn a_forced_correction_overrides_a_dictionary_word_ignoring_case() {
+        let forced_first = common::checker(&["ther", "there"])
+            .with_forced(forced(&[("ther", "there")]))
+            .with_ignore_case(true);
+        let ignore_case_first = common::checker(&["ther", "there"])
+            .with_ignore_case(true)
+            .with_forced(forced(&[("ther", "there")]));
+
+        for checker in [forced_first, ignore_case_first] {
+            assert_eq!(checker.correct_word("ther"), "there");
+            assert_eq!(checker.correct_word("Ther"), "There");
+        }
+    }
+
+    #[test]
+    fn capitalised_keys_match_up_to_case_when_ignoring_case() {
+        let checker = common::checker(&["tea", "the"])
+            .with_ignore_case(true)
+            .with_forced(forced(&[("Teh", "The")]));
+
+        assert_eq!(checker.correct_word("Teh"), "The");
+        assert_eq!(checker.correct_word("teh"), "The");
+    }
+
+    #[test]
+    fn the_ignore_heuristics_see_forced_keys_up_to_case() {
+        let checker = common::checker(&["http"])
+            .with_ignore_case(true)
+            .with_skip_acronyms(true)
+            .with_forced(forced(&[("htpp", "http")]));
+
+        assert_eq!(checker.correct_word("HTPP"), "HTTP");
+    }
+}
+
+mod levenshtein_bounded {
+    use homework_03::{SpellChecker, levenshtein_bounded};
+    use strsim::levenshtein;
+
+    const PAIRS: [(&str, &str); 8] = [
+        ("", ""),
+        ("", "abc"),
+        ("kitten", "sitting"),
+        ("flaw", "lawn"),
+        ("teh", "the"),
+        ("héllo", "hello"),
+        ("abcdef", "fedcba"),
+        ("same", "same"),
+    ];
+
+    #[test]
+    fn agrees_with_levenshtein_under_the_cap() {
+        for (a, b) in PAIRS {
+            let distance = levenshtein(a, b);
+            for max in distance..distance + 3 {
+                assert_eq!(
+                    levenshtein_bounded(a, b, max),
+                    Some(distance),
+                    "{a} {b} {max}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn none_above_the_cap() {
+        for (a, b) in PAIRS {
+            let distance = levenshtein(a, b);
+            for max in 0..distance {
+                assert_eq!(levenshtein_bounded(a, b, max), None, "{a} {b} {max}");
+            }
+        }
+    }
+
+    #[test]
+    fn long_junk_is_abandoned_early() {
+        let junk = "x".repeat(500);
+        let candidate = "y".repeat(500);
+        assert_eq!(levenshtein_bounded(&junk, &candidate, 2), None);
+        assert_eq!(levenshtein_bounded(&junk, "short", 10), None);
+    }
+
+    #[test]
+    fn corrections_are_unchanged() {
+        let words = ["apple", "applet", "cart", "cat", "coat", "the", "then"];
+        let checker = SpellChecker::from_words(words.into_iter().map(String::from))
+            .unwrap()
+            .with_exhaustive(true);
+        for (word, expected) in [
+            ("aple", "apple"),
+            ("caat", "cart"),
+            ("teh", "the"),
+            ("thn", "the"),
+        ] {
+            assert_eq!(checker.correct_word(word), expected);
+        }
+    }
+}
+
+mod line_callback {
+    use crate::common;
+    use homework_03::{ParseOptions, WordList};
+
+    #[test]
+    fn callback_fires_once_per_line_in_order() {
+        let checker = common::checker(&["apple", "banana", "cherry"]);
+        let content = "0001 aple\n0002 banana\n0003 chery bananna\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+
+        let mut seen = Vec::new();
+        let corrected =
+            checker.correct_word_lists_with(&word_lists, |index, original, corrected| {
+                seen.push((index, original.id.clone(), corrected.to_string()));
+            });
+
+        assert_eq!(
+            seen,
+            vec![
+                (0, "0001".to_string(), "0001 apple".to_string()),
+                (1, "0002".to_string(), "0002 banana".to_string()),
+                (2, "0003".to_string(), "0003 cherry banana".to_string()),
+            ]
+        );
+        let written: Vec<String> = corrected.iter().map(ToString::to_string).collect();
+        assert_eq!(written, ["0001 apple", "0002 banana", "0003 cherry banana"]);
+    }
+}
+
+mod max_candidates {
+    use homework_03::SpellChecker;
+
+    /// Every three-letter lowercase word, plus "xy".
+    fn large_checker() -> SpellChecker {
+        let letters = 'a'..='z';
+        let words = letters.clone().flat_map(|a| {
+            letters.clone().flat_map(move |b| {
+                ('a'..='z').map(move |c| [a, b, c].into_iter().collect::<String>())
+            })
+        });
+        SpellChecker::from_words(words.chain(["xy".to_string()])).unwrap()
+    }
+
+    #[test]
+    fn a_one_char_input_measures_at_most_the_cap() {
+        let checker = large_checker().with_max_candidates(100);
+
+        assert_eq!(checker.correct_word("x"), "xy");
+        assert!(checker.comparison_stats().comparisons <= 100);
+    }
+
+    #[test]
+    fn the_cap_keeps_candidates_sharing_the_first_letter() {
+        let checker = large_checker().with_max_candidates(10);
+
+        let suggestions = checker.correct_word_candidates("q", 20);
+        assert_eq!(suggestions.len(), 10);
+        assert!(suggestions.iter().all(|s| s.word().starts_with('q')));
+    }
+
+    #[test]
+    fn the_default_cap_leaves_small_searches_alone() {
+        let capped = large_checker();
+        let uncapped = large_checker().with_exhaustive(true);
+
+        assert_eq!(capped.correct_word("x"), "xy");
+        assert_eq!(uncapped.correct_word("x"), "xy");
+        assert_eq!(
+            capped.comparison_stats().comparisons,
+            uncapped.comparison_stats().comparisons
+        );
+    }
+}
+
+mod max_corrections_per_line {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    const WORDS: &[&str] = &["apple", "banana", "cherry", "date"];
+
+    fn corrected(checker: &SpellChecker, line: &str) -> String {
+        let word_list = WordList::parse_content(line, &ParseOptions::default())
+            .unwrap()
+            .remove(0);
+        checker.correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn one_correction_goes_to_the_closest_word() {
+        let checker = common::checker(WORDS).with_max_corrections_per_line(1);
+
+        // "bananxx" is two edits from "banana", "aple" and "chery" only one.
+        assert_eq!(
+            corrected(&checker, "0001 bananxx chery aple"),
+            "0001 bananxx cherry aple"
+        );
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_token() {
+        let checker = common::checker(WORDS).with_max_corrections_per_line(1);
+
+        assert_eq!(
+            corrected(&checker, "0001 aple chery dat"),
+            "0001 apple chery dat"
+        );
+    }
+
+    #[test]
+    fn known_words_do_not_use_up_the_limit() {
+        let checker = common::checker(WORDS).with_max_corrections_per_line(1);
+
+        assert_eq!(
+            corrected(&checker, "0001 apple banana chery"),
+            "0001 apple banana cherry"
+        );
+    }
+
+    #[test]
+    fn the_limit_applies_per_line() {
+        let checker = common::checker(WORDS).with_max_corrections_per_line(1);
+        let word_lists = WordList::parse_content(
+            "0001 aple chery\n0002 dat bnana\n",
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        let lines: Vec<String> = checker
+            .correct_all(&word_lists)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, ["0001 apple chery", "0002 date bnana"]);
+    }
+
+    #[test]
+    fn a_higher_limit_corrects_more_words() {
+        let checker = common::checker(WORDS).with_max_corrections_per_line(2);
+
+        assert_eq!(
+            corrected(&checker, "0001 bananxx chery aple"),
+            "0001 bananxx cherry apple"
+        );
+    }
+}
+
+mod max_distance_ratio {
+    use crate::common;
+
+    const WORDS: &[&str] = &["receive", "to", "the", "separate", "definitely"];
+
+    #[test]
+    fn a_long_word_may_take_more_edits() {
+        let checker = common::checker(WORDS).with_max_distance_ratio(0.3);
+
+        // 7 characters * 0.3 -> 2 edits.
+        assert_eq!(checker.correct_word("recieve"), "receive");
+        // 10 characters * 0.3 -> 3 edits.
+        assert_eq!(checker.correct_word("definatly"), "definitely");
+    }
+
+    #[test]
+    fn a_short_word_takes_none() {
+        let checker = common::checker(WORDS).with_max_distance_ratio(0.3);
+
+        // 2 characters * 0.3 -> no edits at all.
+        assert_eq!(checker.correct_word("ta"), "ta");
+        // 3 characters * 0.3 -> still none.
+        assert_eq!(checker.correct_word("teh"), "teh");
+    }
+
+    #[test]
+    fn the_limit_is_rounded_down() {
+        // 8 characters * 0.25 -> exactly 2; 7 * 0.25 -> 1.75, so 1.
+        let checker = common::checker(WORDS).with_max_distance_ratio(0.25);
+
+        assert_eq!(checker.correct_word("seperete"), "separate");
+        assert_eq!(checker.correct_word("recieve"), "recieve");
+    }
+
+    #[test]
+    fn the_smaller_of_both_limits_applies() {
+        let absolute_smaller = common::checker(WORDS)
+            .with_max_distance_ratio(0.3)
+            .with_max_distance(1);
+        assert_eq!(absolute_smaller.correct_word("recieve"), "recieve");
+        assert_eq!(absolute_smaller.correct_word("recive"), "receive");
+
+        let ratio_smaller = common::checker(WORDS)
+            .with_max_distance(5)
+            .with_max_distance_ratio(0.3);
+        assert_eq!(ratio_smaller.correct_word("teh"), "teh");
+    }
+}
+
+mod non_lexical {
+    use crate::common;
+
+    const WORDS: &[&str] = &["receive", "that", "http", "vi"];
+
+    #[test]
+    fn every_token_is_corrected_without_the_heuristics() {
+        let checker = common::checker(WORDS);
+        assert_ne!(checker.correct_word("HTTP"), "HTTP");
+        assert_ne!(checker.correct_word("v2.1"), "v2.1");
+        assert_eq!(checker.correct_word("recieve"), "receive");
+    }
+
+    #[test]
+    fn acronyms_and_symbol_heavy_tokens_are_skipped() {
+        let checker = common::checker(WORDS)
+            .with_skip_acronyms(true)
+            .with_max_symbol_ratio(0.5);
+        assert_eq!(checker.correct_word("HTTP"), "HTTP");
+        assert_eq!(checker.correct_word("v2.1"), "v2.1");
+        assert_eq!(checker.correct_word("recieve"), "receive");
+    }
+
+    #[test]
+    fn heuristics_apply_with_ignore_case() {
+        let checker = common::checker(WORDS)
+            .with_ignore_case(true)
+            .with_skip_acronyms(true)
+            .with_max_symbol_ratio(0.5);
+        assert_eq!(checker.correct_word("HTTP"), "HTTP");
+        assert_eq!(checker.correct_word("v2.1"), "v2.1");
+        assert_eq!(checker.correct_word("Recieve"), "Receive");
+    }
+}
+
+mod phonetic {
+    use crate::common;
+    use homework_03::{Phonetic, SpellChecker, double_metaphone, soundex};
+    use std::collections::HashMap;
+
+    fn keys(word: &str) -> (String, Option<String>) {
+        double_metaphone(word)
+    }
+
+    fn key(primary: &str, alternate: Option<&str>) -> (String, Option<String>) {
+        (primary.to_string(), alternate.map(String::from))
+    }
+
+    #[test]
+    fn soundex_matches_reference_codes() {
+        for (word, code) in [
+            ("Robert", "R163"),
+            ("Rupert", "R163"),
+            ("Rubin", "R150"),
+            ("Ashcraft", "A261"),
+            ("Tymczak", "T522"),
+            ("Pfister", "P236"),
+            ("Honeyman", "H555"),
+            ("Lee", "L000"),
+        ] {
+            assert_eq!(soundex(word), code, "{}", word);
+        }
+    }
+
+    #[test]
+    fn soundex_ignores_case_and_non_letters() {
+        assert_eq!(soundex("robert"), soundex("ROBERT"));
+        assert_eq!(soundex("O'Brien"), soundex("OBrien"));
+        assert_eq!(soundex(""), "");
+    }
+
+    #[test]
+    fn double_metaphone_matches_reference_keys() {
+        assert_eq!(keys("Schmidt"), key("XMT", Some("SMT")));
+        assert_eq!(keys("Smith"), key("SM0", Some("XMT")));
+        assert_eq!(keys("knight"), key("NT", None));
+        assert_eq!(keys("phone"), key("FN", None));
+    }
+
+    #[test]
+    fn double_metaphone_keys_misspellings_like_their_words() {
+        assert_eq!(keys("nite").0, keys("night").0);
+        assert_eq!(keys("fone").0, keys("phone").0);
+        assert_eq!(keys("kat").0, keys("cat").0);
+    }
+
+    #[test]
+    fn algorithms_are_selected_by_name() {
+        assert_eq!(Phonetic::from_name("soundex"), Some(Phonetic::Soundex));
+        assert_eq!(
+            Phonetic::from_name("Double-Metaphone"),
+            Some(Phonetic::DoubleMetaphone)
+        );
+        assert_eq!(
+            Phonetic::from_name("metaphone"),
+            Some(Phonetic::DoubleMetaphone)
+        );
+        assert_eq!(Phonetic::from_name("nysiis"), None);
+    }
+
+    fn checker(phonetic: Option<Phonetic>) -> SpellChecker {
+        common::checker(&["knight", "might", "nice", "night", "one", "phone", "sight"])
+            .with_phonetic(phonetic)
+    }
+
+    #[test]
+    fn the_phonetic_fallback_prefers_words_that_sound_alike() {
+        assert_eq!(checker(None).correct_word("fone"), "one");
+        assert_eq!(checker(None).correct_word("nite"), "nice");
+
+        let checker = checker(Some(Phonetic::DoubleMetaphone));
+        assert_eq!(checker.correct_word("fone"), "phone");
+        assert_eq!(checker.correct_word("nite"), "night");
+    }
+
+    #[test]
+    fn the_phonetic_fallback_also_applies_under_a_frequency_list() {
+        let frequencies = HashMap::from([("one".to_string(), 100), ("phone".to_string(), 5)]);
+        let checker = checker(Some(Phonetic::DoubleMetaphone)).with_frequencies(frequencies);
+
+        assert_eq!(checker.correct_word("fone"), "phone");
+    }
+
+    #[test]
+    fn frequencies_settle_ties_among_the_nearest_spellings() {
+        // "might", "night" and "sight" are all one edit from "hight".
+        let frequencies = HashMap::from([("sight".to_string(), 40), ("might".to_string(), 10)]);
+        let checker = checker(None).with_frequencies(frequencies);
+
+        assert_eq!(checker.correct_word("hight"), "sight");
+    }
+
+    #[test]
+    fn phonetic_matches_are_ranked_by_edit_distance() {
+        // "knight" and "night" share the key NT; "night" is one edit closer.
+        let checker = checker(Some(Phonetic::DoubleMetaphone));
+        assert_eq!(checker.correct_word("nyte"), "night");
+    }
+
+    #[test]
+    fn soundex_can_drive_the_fallback_too() {
+        // "cat" is first among the one-edit spellings but codes C300, not K300.
+        let checker = common::checker(&["cat", "kate"]);
+        assert_eq!(checker.correct_word("kat"), "cat");
+        let checker = checker.with_phonetic(Some(Phonetic::Soundex));
+        assert_eq!(checker.correct_word("kat"), "kate");
+    }
+
+    #[test]
+    fn a_close_spelling_that_sounds_alike_is_kept() {
+        let checker = checker(Some(Phonetic::DoubleMetaphone));
+        assert_eq!(checker.correct_word("migt"), "might");
+    }
+}
+
+mod proper_nouns {
+    use crate::common;
+    use homework_03::SpellChecker;
+
+    fn checker() -> SpellChecker {
+        common::checker(&["Paris", "Polish", "polish", "the", "March", "march"])
+            .with_ignore_case(true)
+    }
+
+    #[test]
+    fn exact_matches_take_priority_over_case_folding() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("Polish"), "Polish");
+        assert_eq!(checker.correct_word("polish"), "polish");
+        assert_eq!(checker.correct_word("March"), "March");
+        assert_eq!(checker.correct_word("march"), "march");
+    }
+
+    #[test]
+    fn case_only_differences_count_as_known() {
+        let checker = checker();
+        assert!(checker.contains_word("Paris"));
+        assert!(checker.contains_word("paris"));
+        assert!(checker.contains_word("The"));
+        assert!(!checker.contains_word("Pariss"));
+    }
+
+    #[test]
+    fn case_sensitive_checkers_only_know_exact_spellings() {
+        let checker = checker().with_ignore_case(false);
+        assert!(checker.contains_word("Paris"));
+        assert!(!checker.contains_word("paris"));
+    }
+
+    #[test]
+    fn proper_nouns_are_restored_when_normalizing_case() {
+        let checker = checker().with_normalize_case(true);
+        assert_eq!(checker.correct_word("paris"), "Paris");
+        assert_eq!(checker.correct_word("PARIS"), "Paris");
+        assert_eq!(checker.correct_word("The"), "the");
+        assert_eq!(checker.correct_word("polish"), "polish");
+    }
+
+    #[test]
+    fn sentence_initial_capitals_are_kept_as_written() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("The"), "The");
+        assert_eq!(checker.correct_word("Teh"), "The");
+    }
+}
+
+mod same_script {
+    use crate::common;
+    use homework_03::Script;
+
+    #[test]
+    fn dominant_script_ignores_digits_and_punctuation() {
+        assert_eq!(Script::dominant("word"), Script::Latin);
+        assert_eq!(Script::dominant("слово"), Script::Cyrillic);
+        assert_eq!(Script::dominant("2024-"), Script::Common);
+        // Two Cyrillic letters outweigh one Latin letter.
+        assert_eq!(Script::dominant("саt"), Script::Cyrillic);
+    }
+
+    #[test]
+    fn a_latin_typo_is_never_corrected_to_a_cyrillic_word() {
+        let checker = common::checker(&["дом", "house"]);
+        assert_eq!(checker.correct_word("dom"), "дом");
+
+        let checker = checker.with_same_script(true);
+        assert_eq!(checker.correct_word("dom"), "house");
+    }
+
+    #[test]
+    fn a_latin_typo_is_kept_when_only_cyrillic_words_are_near() {
+        let checker = common::checker(&["кот", "дом"]).with_same_script(true);
+        assert_eq!(checker.correct_word("kot"), "kot");
+        assert_eq!(checker.correct_word("дон"), "дом");
+    }
+
+    #[test]
+    fn homoglyph_entries_are_not_suggested_for_latin_input() {
+        // "саt" spells its first two letters in Cyrillic.
+        let checker = common::checker(&["саt", "dog"]);
+        assert_eq!(checker.correct_word("cat"), "саt");
+        assert_eq!(checker.with_same_script(true).correct_word("cat"), "dog");
+    }
+
+    #[test]
+    fn digits_do_not_change_the_script_of_a_word() {
+        let checker = common::checker(&["дом"]).with_same_script(true);
+        assert_eq!(checker.correct_word("д0м"), "дом");
+    }
+}
+
+mod seed {
+    use crate::common;
+    use homework_03::{DEFAULT_SEED, ParseOptions, SpellChecker, WordList};
+    use std::collections::HashMap;
+
+    /// Many equally distant candidates with equal frequencies, so only the
+    /// tie-break decides and any hash-order dependence would show.
+    fn checker(seed: u64) -> SpellChecker {
+        let words = [
+            "bat", "cat", "fat", "hat", "mat", "pat", "rat", "sat", "vat",
+        ];
+        let frequencies: HashMap<String, u64> = words.iter().map(|w| (w.to_string(), 5)).collect();
+        common::checker(&words)
+            .with_frequencies(frequencies)
+            .with_seed(seed)
+    }
+
+    fn run(seed: u64) -> Vec<String> {
+        let content = "0001 xat zat qat\n0002 kat jat\n0003 wat/yat\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+        checker(seed)
+            .correct_all(&word_lists)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn the_same_seed_produces_identical_output() {
+        let first = run(42);
+        for _ in 0..5 {
+            assert_eq!(run(42), first);
+        }
+    }
+
+    #[test]
+    fn the_default_seed_is_fixed() {
+        let checker = common::checker(&["word"]);
+        assert_eq!(checker.seed(), DEFAULT_SEED);
+        assert_eq!(run(DEFAULT_SEED), run(DEFAULT_SEED));
+    }
+
+    #[test]
+    fn generators_from_the_same_seed_draw_the_same_samples() {
+        let sample = |seed| checker(seed).rng().sample_indices(1_000, 10);
+
+        assert_eq!(sample(7), sample(7));
+        assert_ne!(sample(7), sample(8));
+    }
+}
+
+mod skip_if_clean {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    fn checker() -> SpellChecker {
+        common::checker(&["the", "quick", "brown", "fox", "jumps"]).with_skip_if_clean(0.75)
+    }
+
+    fn line(text: &str) -> WordList {
+        WordList::parse_line(1, text, &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn known_ratio_counts_dictionary_words() {
+        let checker = checker();
+        assert_eq!(checker.known_ratio(&line("0001 the quick brown fox")), 1.0);
+        assert_eq!(checker.known_ratio(&line("0001 the quikc brown fox")), 0.75);
+        assert_eq!(checker.known_ratio(&line("0001 teh quikc brown fox")), 0.5);
+    }
+
+    #[test]
+    fn clean_lines_pass_through_without_distance_work() {
+        let checker = checker();
+        let clean = line("0001 the quikc brown fox");
+
+        let (corrected, changes) = checker.correct_word_list_tracked(&clean);
+
+        assert_eq!(corrected.to_string(), "0001 the quikc brown fox");
+        assert!(changes.is_empty());
+        assert_eq!(checker.comparison_stats().comparisons, 0);
+    }
+
+    #[test]
+    fn dirty_lines_are_corrected() {
+        let checker = checker();
+        let dirty = line("0001 teh quikc brown fox");
+
+        let corrected = checker.correct_word_list(&dirty);
+
+        assert_eq!(corrected.to_string(), "0001 the quick brown fox");
+        assert!(checker.comparison_stats().comparisons > 0);
+    }
+}
+
+mod stream {
+    use crate::common;
+    use homework_03::{CorrectionStats, ParseOptions, SpellError, correct_stream};
+    use std::io::Cursor;
+    use std::sync::atomic::AtomicBool;
+
+    fn stream(input: &str) -> (String, Result<CorrectionStats, SpellError>) {
+        let checker = common::checker(&["apple", "banana", "cherry"]);
+        let mut output = Vec::new();
+        let result = correct_stream(
+            &checker,
+            Cursor::new(input),
+            &mut output,
+            &ParseOptions::default(),
+            &AtomicBool::new(false),
+        );
+        (String::from_utf8(output).unwrap(), result)
+    }
+
+    #[test]
+    fn entries_are_corrected_line_by_line() {
+        let (output, stats) = stream("0001 aple banana\n0002 cherry\n0003 bananna chery\n");
+
+        assert_eq!(
+            output,
+            "0001 apple banana\n0002 cherry\n0003 banana cherry\n"
+        );
+        assert_eq!(
+            stats.unwrap(),
+            CorrectionStats {
+                lines: 3,
+                words: 5,
+                changed_lines: 2,
+                ..CorrectionStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn blank_lines_and_a_bom_are_skipped() {
+        let (output, stats) = stream("\u{feff}0001 apple\n\n   \n0002 chery");
+
+        assert_eq!(output, "0001 apple\n0002 cherry\n");
+        assert_eq!(stats.unwrap().lines, 2);
+    }
+
+    #[test]
+    fn empty_input_writes_nothing() {
+        let (output, stats) = stream("");
+
+        assert_eq!(output, "");
+        assert_eq!(stats.unwrap(), CorrectionStats::default());
+    }
+
+    #[test]
+    fn an_invalid_line_fails_after_the_lines_before_it() {
+        let (output, result) = stream("0001 aple\nbad\n0002 apple\n");
+
+        assert_eq!(output, "0001 apple\n");
+        assert!(matches!(
+            result,
+            Err(SpellError::InvalidLine { line: 2, .. })
+        ));
+    }
+}
+
+mod suggestion {
+    use crate::common;
+    use homework_03::Suggestion;
+    use std::collections::HashMap;
+
+    fn suggestion(word: &str, distance: usize, confidence: f64) -> Suggestion {
+        Suggestion::new(word.to_string(), distance, confidence)
+    }
+
+    #[test]
+    fn sorts_by_distance_then_confidence_then_word() {
+        let mut suggestions = [
+            suggestion("zeta", 2, 0.9),
+            suggestion("beta", 1, 0.1),
+            suggestion("alpha", 1, 0.1),
+            suggestion("gamma", 1, 0.5),
+        ];
+        suggestions.sort();
+
+        let words: Vec<&str> = suggestions.iter().map(Suggestion::word).collect();
+        assert_eq!(words, ["gamma", "alpha", "beta", "zeta"]);
+    }
+
+    #[test]
+    fn ordering_does_not_depend_on_input_order() {
+        let expected = vec![
+            suggestion("cat", 1, 2.0),
+            suggestion("bat", 1, 1.0),
+            suggestion("hat", 1, 1.0),
+            suggestion("chart", 2, 5.0),
+        ];
+        let mut reversed: Vec<Suggestion> = expected.iter().rev().cloned().collect();
+        reversed.sort();
+        assert_eq!(reversed, expected);
+    }
+
+    #[test]
+    fn candidates_expose_word_distance_and_confidence() {
+        let checker = common::checker(&["bat", "cat", "hat"])
+            .with_frequencies(HashMap::from([("hat".to_string(), 9)]));
+
+        let candidates = checker.correct_word_candidates("xat", 3);
+
+        assert_eq!(candidates[0].word(), "hat");
+        assert_eq!(candidates[0].distance(), 1);
+        assert!(candidates[0].confidence() > candidates[1].confidence());
+        let rest: Vec<&str> = candidates[1..].iter().map(Suggestion::word).collect();
+        assert_eq!(rest, ["bat", "cat"]);
+    }
+}
+
+mod tie_break {
+    use crate::common;
+    use homework_03::{SpellChecker, TieBreak};
+    use std::collections::HashMap;
+
+    fn checker() -> SpellChecker {
+        common::checker(&["cart", "cat", "coat"])
+            .with_frequencies(HashMap::from([("coat".to_string(), 10)]))
+    }
+
+    #[test]
+    fn every_candidate_is_one_edit_away() {
+        let checker = checker();
+        for candidate in ["cart", "cat", "coat"] {
+            assert_eq!(checker.edit_distance("caat", candidate), 1);
+        }
+    }
+
+    #[test]
+    fn strategies_pick_different_candidates_for_the_same_word() {
+        let pick = |tie_break| checker().with_tie_break(tie_break).correct_word("caat");
+
+        assert_eq!(pick(TieBreak::Alphabetical), "cart");
+        assert_eq!(pick(TieBreak::Frequency), "coat");
+        assert_eq!(pick(TieBreak::ShortestFirst), "cat");
+    }
+
+    #[test]
+    fn a_loaded_frequency_list_decides_by_default() {
+        assert_eq!(checker().correct_word("caat"), "coat");
+        let without_frequencies = common::checker(&["cart", "cat", "coat"]);
+        assert_eq!(without_frequencies.correct_word("caat"), "cart");
+    }
+
+    #[test]
+    fn shortest_first_still_prefers_the_smaller_distance() {
+        let checker = common::checker(&["ab", "abcdx"]).with_tie_break(TieBreak::ShortestFirst);
+        assert_eq!(checker.correct_word("abcd"), "abcdx");
+    }
+}
+
+mod token_outcomes {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, TokenOutcome, WordList};
+
+    fn checker() -> SpellChecker {
+        common::checker(&["apple", "cat", "the"]).with_max_distance(1)
+    }
+
+    fn parse(line: &str) -> WordList {
+        WordList::parse_line(1, line, &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn every_outcome_on_one_line() {
+        let (corrected, outcomes) =
+            checker().correct_word_list_detailed(&parse("0001 the aple xyzzy 42"));
+
+        assert_eq!(corrected.to_string(), "0001 the apple xyzzy 42");
+        assert_eq!(
+            outcomes,
+            [
+                TokenOutcome::Unchanged,
+                TokenOutcome::Separator,
+                TokenOutcome::Corrected {
+                    from: "aple".to_string(),
+                    to: "apple".to_string(),
+                    distance: 1,
+                },
+                TokenOutcome::Separator,
+                TokenOutcome::NoSuggestion,
+                TokenOutcome::Separator,
+                TokenOutcome::Unchanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn dropped_words_are_reported() {
+        let checker = checker().with_drop_unknown(true);
+        let (corrected, outcomes) = checker.correct_word_list_detailed(&parse("0001 cat xyzzy"));
+
+        assert_eq!(corrected.to_string(), "0001 cat");
+        assert_eq!(
+            outcomes,
+            [
+                TokenOutcome::Unchanged,
+                TokenOutcome::Separator,
+                TokenOutcome::Dropped,
+            ]
+        );
+    }
+
+    #[test]
+    fn tracked_changes_agree() {
+        let checker = checker();
+        let word_list = parse("0001 teh aple catt xyzzy");
+        let (_, changes) = checker.correct_word_list_tracked(&word_list);
+        let (_, outcomes) = checker.correct_word_list_detailed(&word_list);
+
+        let corrected: Vec<(String, String)> = outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome {
+                TokenOutcome::Corrected { from, to, .. } => Some((from, to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(changes, corrected);
+    }
+}
+
+mod whitespace_guard {
+    use crate::common;
+    use homework_03::{SpellChecker, Strategy};
+    use std::collections::HashMap;
+
+    fn checker() -> SpellChecker {
+        // Trimming removes surrounding whitespace while loading, but an entry
+        // can still carry it inside.
+        common::checker(&["ice cream", "icecap"])
+    }
+
+    #[test]
+    fn a_whitespace_only_difference_is_not_a_correction() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("icecream"), "icecream");
+        assert_eq!(checker.decide("icecream").strategy, Strategy::Kept);
+    }
+
+    #[test]
+    fn other_corrections_are_unaffected() {
+        assert_eq!(checker().correct_word("icecapp"), "icecap");
+        assert_eq!(checker().correct_word("icecrem"), "ice cream");
+    }
+
+    #[test]
+    fn forced_corrections_still_apply() {
+        let checker = checker().with_forced(HashMap::from([(
+            "icecream".to_string(),
+            "ice cream".to_string(),
+        )]));
+        assert_eq!(checker.correct_word("icecream"), "ice cream");
+    }
+}
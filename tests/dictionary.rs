@@ -0,0 +1,608 @@
+mod common;
+
+mod accepted {
+    use homework_03::{Dictionary, SpellChecker, Strategy};
+    use std::collections::HashMap;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn checker() -> SpellChecker {
+        SpellChecker::from_words(words(&["color", "colour", "shop", "show"]))
+            .unwrap()
+            .with_accepted(Dictionary::from_words(words(&["shoppe", "whilst"])).unwrap())
+    }
+
+    #[test]
+    fn main_dictionary_words_are_known() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("shop"), "shop");
+        assert_eq!(checker.decide("shop").strategy, Strategy::Known);
+    }
+
+    #[test]
+    fn secondary_words_are_kept() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("shoppe"), "shoppe");
+        assert_eq!(checker.correct_word("whilst"), "whilst");
+        assert_eq!(checker.decide("shoppe").strategy, Strategy::Accepted);
+        assert!(!checker.contains_word("shoppe"));
+    }
+
+    #[test]
+    fn secondary_words_are_never_suggested() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("shopp"), "shop");
+        assert_ne!(checker.correct_word("whilt"), "whilst");
+        assert!(
+            checker
+                .correct_word_candidates("shoppee", 10)
+                .iter()
+                .all(|suggestion| suggestion.word() != "shoppe")
+        );
+    }
+
+    #[test]
+    fn unknown_words_are_corrected() {
+        let checker = checker();
+        assert_eq!(checker.correct_word("colr"), "color");
+        assert_eq!(checker.decide("colr").strategy, Strategy::Nearest);
+    }
+
+    #[test]
+    fn forced_corrections_still_apply() {
+        let checker =
+            checker().with_forced(HashMap::from([("shoppe".to_string(), "shop".to_string())]));
+        assert_eq!(checker.correct_word("shoppe"), "shop");
+    }
+
+    #[test]
+    fn case_folding_applies_to_the_secondary_dictionary() {
+        let checker = checker().with_ignore_case(true);
+        assert_eq!(checker.correct_word("Shoppe"), "Shoppe");
+    }
+}
+
+mod canonical {
+    use crate::common;
+
+    const WORDS: &[&str] = &["Apple", "NASA", "Polish", "paris", "polish"];
+
+    #[test]
+    fn exact_spellings_are_returned_as_they_are() {
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.canonical("NASA"), Some("NASA"));
+        assert_eq!(checker.canonical("polish"), Some("polish"));
+        assert_eq!(checker.canonical("Polish"), Some("Polish"));
+    }
+
+    #[test]
+    fn differing_case_maps_to_the_dictionary_spelling() {
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.canonical("apple"), Some("Apple"));
+        assert_eq!(checker.canonical("APPLE"), Some("Apple"));
+        assert_eq!(checker.canonical("nasa"), Some("NASA"));
+        assert_eq!(checker.canonical("Paris"), Some("paris"));
+    }
+
+    #[test]
+    fn the_first_entry_in_sorted_order_wins_among_case_variants() {
+        assert_eq!(common::checker(WORDS).canonical("POLISH"), Some("Polish"));
+    }
+
+    #[test]
+    fn absent_words_have_no_canonical_form() {
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.canonical("aple"), None);
+        assert_eq!(checker.canonical(""), None);
+    }
+}
+
+mod dictionary_retry {
+    use homework_03::{Dictionary, LoadOptions, SpellError};
+    use std::io::{self, ErrorKind};
+    use std::time::Duration;
+
+    fn options(retries: usize) -> LoadOptions {
+        LoadOptions {
+            retries,
+            retry_backoff: Duration::ZERO,
+            ..LoadOptions::default()
+        }
+    }
+
+    /// A read that fails with `kind` `failures` times before returning `content`.
+    fn flaky(
+        kind: ErrorKind,
+        failures: usize,
+        content: &str,
+        attempts: &mut usize,
+    ) -> impl FnMut() -> io::Result<String> {
+        move || {
+            *attempts += 1;
+            if *attempts <= failures {
+                Err(io::Error::new(kind, "file is locked"))
+            } else {
+                Ok(content.to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn a_transient_failure_succeeds_on_retry() {
+        let mut attempts = 0;
+        let read = flaky(ErrorKind::WouldBlock, 2, "apple\ncat\n", &mut attempts);
+        let dictionary = Dictionary::load_with(read, &options(3)).unwrap();
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn retries_are_off_by_default() {
+        let mut attempts = 0;
+        let read = flaky(ErrorKind::WouldBlock, 1, "apple\n", &mut attempts);
+        let error = Dictionary::load_with(read, &LoadOptions::default()).unwrap_err();
+        assert!(matches!(error, SpellError::Io(_)));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn giving_up_reports_the_attempts() {
+        let mut attempts = 0;
+        let read = flaky(ErrorKind::TimedOut, 10, "apple\n", &mut attempts);
+        let error = Dictionary::load_with(read, &options(2)).unwrap_err();
+        assert!(matches!(&error, SpellError::Io(message) if message.contains("after 3 attempts")));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn permanent_failures_are_not_retried() {
+        for kind in [ErrorKind::NotFound, ErrorKind::InvalidData] {
+            let mut attempts = 0;
+            let read = flaky(kind, 10, "apple\n", &mut attempts);
+            assert!(matches!(
+                Dictionary::load_with(read, &options(5)),
+                Err(SpellError::Io(_))
+            ));
+            assert_eq!(attempts, 1, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn an_empty_read_is_an_empty_dictionary() {
+        let mut attempts = 0;
+        let read = flaky(ErrorKind::WouldBlock, 0, "\n\n", &mut attempts);
+        assert!(matches!(
+            Dictionary::load_with(read, &options(5)),
+            Err(SpellError::EmptyDictionary)
+        ));
+        assert_eq!(attempts, 1);
+    }
+}
+
+mod parallel_loading {
+    use homework_03::{Dictionary, LoadOptions, SplitMix64};
+
+    /// Many lines in scrambled order, with duplicates, padding and blank lines,
+    /// so every loader chunk sees some of each.
+    fn scrambled_content() -> String {
+        let mut rng = SplitMix64::new(7);
+        (0..20_000)
+            .map(|_| match rng.next_u64() % 10 {
+                0 => String::new(),
+                1 => format!("  w{}\t", rng.next_u64() % 5_000),
+                _ => format!("w{}", rng.next_u64() % 5_000),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn loading_content_matches_the_serial_word_path() {
+        let content = scrambled_content();
+        let loaded = Dictionary::from_content(&content, &LoadOptions::default()).unwrap();
+        let serial = Dictionary::from_words(content.lines().map(String::from)).unwrap();
+
+        assert_eq!(loaded.words(), serial.words());
+    }
+
+    #[test]
+    fn loaded_words_are_sorted_and_unique() {
+        let content = scrambled_content();
+        let dictionary = Dictionary::from_content(&content, &LoadOptions::default()).unwrap();
+
+        let mut expected: Vec<String> = content
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(dictionary.words(), expected.as_slice());
+    }
+}
+
+mod prefix {
+    use crate::common;
+
+    const WORDS: &[&str] = &["car", "card", "care", "cart", "cat", "dog", "éclair"];
+
+    #[test]
+    fn returns_every_word_with_a_present_prefix_in_order() {
+        let checker = common::checker(WORDS);
+        assert_eq!(
+            checker.words_with_prefix("car"),
+            ["car", "card", "care", "cart"]
+        );
+        assert_eq!(checker.words_with_prefix("ca").len(), 5);
+        assert_eq!(checker.words_with_prefix("dog"), ["dog"]);
+        assert_eq!(checker.words_with_prefix("é"), ["éclair"]);
+    }
+
+    #[test]
+    fn an_absent_prefix_returns_an_empty_slice() {
+        let checker = common::checker(WORDS);
+        assert!(checker.words_with_prefix("cz").is_empty());
+        assert!(checker.words_with_prefix("a").is_empty());
+        assert!(checker.words_with_prefix("zebra").is_empty());
+        assert!(checker.words_with_prefix("cards").is_empty());
+    }
+
+    #[test]
+    fn an_empty_prefix_returns_all_words() {
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.words_with_prefix("").len(), checker.len());
+    }
+}
+
+mod shared_dictionary {
+    use homework_03::{Dictionary, EditCosts, LoadOptions, SpellChecker};
+    use std::sync::Arc;
+
+    fn dictionary() -> Arc<Dictionary> {
+        Arc::new(
+            Dictionary::from_content("Paris\napple\ncart\ncat\ncoat\n", &LoadOptions::default())
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn lookups_need_no_checker() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.len(), 5);
+        assert!(dictionary.contains("cat"));
+        assert!(!dictionary.contains("paris"));
+        assert_eq!(dictionary.folded("paris"), Some("Paris"));
+        assert_eq!(dictionary.words_with_prefix("ca"), ["cart", "cat"]);
+        assert_eq!(
+            dictionary.candidates("caat", 1),
+            [("cart", 1), ("cat", 1), ("coat", 1)]
+        );
+    }
+
+    #[test]
+    fn two_checkers_share_one_dictionary() {
+        let dictionary = dictionary();
+        let strict = SpellChecker::from_dictionary(Arc::clone(&dictionary)).with_max_distance(0);
+        let folding = SpellChecker::from_dictionary(Arc::clone(&dictionary)).with_ignore_case(true);
+
+        assert!(Arc::ptr_eq(strict.dictionary(), folding.dictionary()));
+        assert_eq!(Arc::strong_count(&dictionary), 3);
+
+        assert_eq!(strict.correct_word("caat"), "caat");
+        assert_eq!(folding.correct_word("caat"), "cart");
+        assert!(!strict.contains_word("paris"));
+        assert!(folding.contains_word("paris"));
+    }
+
+    #[test]
+    fn checkers_can_use_different_metrics() {
+        let dictionary = dictionary();
+        let plain = SpellChecker::from_dictionary(Arc::clone(&dictionary));
+        let weighted = SpellChecker::from_dictionary(dictionary).with_edit_costs(EditCosts {
+            insertion: 1,
+            deletion: 1,
+            substitution: 3,
+        });
+
+        assert_eq!(plain.correct_word("cot"), "cat");
+        assert_eq!(weighted.correct_word("cot"), "coat");
+    }
+
+    #[test]
+    fn an_owned_dictionary_is_accepted_too() {
+        let dictionary = Dictionary::from_words(["one".to_string()]).unwrap();
+        assert_eq!(SpellChecker::from_dictionary(dictionary).len(), 1);
+    }
+}
+
+mod tiny_dictionary {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellChecker, WordList};
+
+    fn two_word_checker() -> SpellChecker {
+        common::checker(&["apple", "banana"])
+    }
+
+    #[test]
+    fn two_word_dictionary_corrects_everything_by_default() {
+        let checker = two_word_checker();
+        assert_eq!(checker.len(), 2);
+        assert_eq!(checker.correct_word("zebra"), "apple");
+    }
+
+    #[test]
+    fn check_only_keeps_unknown_words_with_a_two_word_dictionary() {
+        let checker = two_word_checker().with_check_only(true);
+        assert!(checker.contains_word("banana"));
+        assert!(!checker.contains_word("zebra"));
+        assert_eq!(checker.correct_word("zebra"), "zebra");
+        assert_eq!(checker.correct_word("aple"), "aple");
+
+        let word_list =
+            WordList::parse_line(1, "0001 aple banana zebra", &ParseOptions::default()).unwrap();
+        let corrected = checker.correct_word_list(&word_list);
+        assert_eq!(corrected.tokens, word_list.tokens);
+    }
+}
+
+mod unmatchable {
+    use homework_03::{LoadOptions, SpellChecker};
+
+    const DICTIONARY: &str = "apple\nice cream\nand/or\ncat\n";
+
+    #[test]
+    fn entries_with_a_separator_are_counted_and_kept_by_default() {
+        let checker = SpellChecker::from_content(DICTIONARY, &LoadOptions::default()).unwrap();
+        assert_eq!(checker.unmatchable_entries(), 2);
+        assert_eq!(checker.len(), 4);
+        assert!(checker.contains_word("ice cream"));
+    }
+
+    #[test]
+    fn they_can_be_dropped() {
+        let options = LoadOptions {
+            drop_unmatchable: true,
+            ..LoadOptions::default()
+        };
+        let checker = SpellChecker::from_content(DICTIONARY, &options).unwrap();
+        assert_eq!(checker.unmatchable_entries(), 2);
+        assert_eq!(checker.len(), 2);
+        assert!(!checker.contains_word("ice cream"));
+    }
+
+    #[test]
+    fn only_configured_separators_count() {
+        let options = LoadOptions {
+            separators: vec![' '],
+            ..LoadOptions::default()
+        };
+        let checker = SpellChecker::from_content(DICTIONARY, &options).unwrap();
+        assert_eq!(checker.unmatchable_entries(), 1);
+    }
+
+    #[test]
+    fn in_memory_words_are_checked_too() {
+        let words = ["ice cream", "cat"].into_iter().map(String::from);
+        assert_eq!(
+            SpellChecker::from_words(words)
+                .unwrap()
+                .unmatchable_entries(),
+            1
+        );
+    }
+}
+
+#[cfg(feature = "fs")]
+mod disk_dictionary {
+    use crate::common;
+    use homework_03::{DiskDictionary, SpellChecker};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const WORDS: &[&str] = &[
+        "apple", "apply", "banana", "cherry", "date", "elder", "fig", "grape", "kiwi", "lemon",
+        "mango", "melon", "olive", "peach", "pear", "plum", "quince",
+    ];
+
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn content() -> String {
+        WORDS.iter().map(|word| format!("{}\n", word)).collect()
+    }
+
+    /// Words to probe: every entry, plus words before, between and after them.
+    fn probes() -> Vec<String> {
+        let mut probes: Vec<String> = WORDS.iter().map(|word| word.to_string()).collect();
+        probes.extend(
+            [
+                "", "a", "aaa", "appl", "applz", "bananas", "cherr", "melons", "pea", "zebra",
+            ]
+            .map(String::from),
+        );
+        probes
+    }
+
+    fn assert_agree(mut disk: DiskDictionary, memory: &SpellChecker) {
+        for probe in probes() {
+            assert_eq!(
+                disk.contains_word(&probe).unwrap(),
+                memory.contains_word(&probe),
+                "membership of '{}'",
+                probe
+            );
+            assert_eq!(
+                disk.correct_word(&probe).unwrap(),
+                memory.correct_word(&probe),
+                "correction of '{}'",
+                probe
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_in_memory_dictionary() {
+        let path = file("disk_plain.txt", &content());
+        assert_agree(DiskDictionary::open(path).unwrap(), &common::checker(WORDS));
+    }
+
+    #[test]
+    fn agrees_with_the_in_memory_dictionary_on_a_bom_file() {
+        let content = format!("\u{feff}{}", content());
+        let path = file("disk_bom.txt", &content);
+
+        let disk = DiskDictionary::open(&path).unwrap();
+        let memory = SpellChecker::new(&path).unwrap();
+        assert!(memory.contains_word("apple"));
+        assert_agree(disk, &memory);
+    }
+
+    #[test]
+    fn numeric_tokens_are_left_alone_by_default() {
+        let path = file("disk_numeric.txt", "one\ntwo\n");
+        let mut disk = DiskDictionary::open(&path).unwrap();
+
+        for token in ["2024", "3.14", "1,000"] {
+            assert_eq!(disk.correct_word(token).unwrap(), token);
+        }
+
+        let mut disk = DiskDictionary::open(&path)
+            .unwrap()
+            .with_ignore_numeric(false);
+        assert_eq!(disk.correct_word("2").unwrap(), "one");
+    }
+
+    #[test]
+    fn rejects_an_unsorted_file() {
+        let path = file("disk_unsorted.txt", "pear\napple\n");
+        assert!(DiskDictionary::open(path).is_err());
+    }
+
+    #[test]
+    fn blank_lines_between_entries_are_skipped() {
+        let path = file(
+            "disk_blank_lines.txt",
+            "apple\n\nbanana\ncherry\n\n\ndate\n",
+        );
+        let mut disk = DiskDictionary::open(&path).unwrap();
+
+        for word in ["apple", "banana", "cherry", "date"] {
+            assert!(
+                disk.contains_word(word).unwrap(),
+                "membership of '{}'",
+                word
+            );
+        }
+        for word in ["", "apples", "coconut", "zebra"] {
+            assert!(
+                !disk.contains_word(word).unwrap(),
+                "membership of '{}'",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_in_memory_dictionary_with_blank_lines() {
+        let content: String = WORDS
+            .iter()
+            .enumerate()
+            .map(|(i, word)| match i % 3 {
+                0 => format!("\n{}\n\n", word),
+                1 => format!("{}\n \n", word),
+                _ => format!("{}\n", word),
+            })
+            .collect();
+        let path = file("disk_blank_lines_all.txt", &content);
+        assert_agree(DiskDictionary::open(path).unwrap(), &common::checker(WORDS));
+    }
+}
+
+#[cfg(feature = "fs")]
+mod reload {
+    use homework_03::{DictionaryWatcher, LoadOptions, SpellError};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Polls until `done` holds, failing the test after [`TIMEOUT`].
+    fn wait_for(mut done: impl FnMut() -> bool) {
+        let start = Instant::now();
+        while !done() {
+            assert!(start.elapsed() < TIMEOUT, "timed out waiting for a reload");
+            thread::sleep(INTERVAL);
+        }
+    }
+
+    #[test]
+    fn a_changed_file_swaps_in_the_new_dictionary() {
+        let path = file("reload_swap.txt", "apple\n");
+        let watcher = DictionaryWatcher::watch(
+            &path,
+            LoadOptions::default(),
+            INTERVAL,
+            |checker| checker.with_max_distance(1),
+            // A poll may catch the file mid-write; the next one reloads it.
+            |_| {},
+        )
+        .unwrap();
+        let before = watcher.checker();
+        assert!(!before.contains_word("banana"));
+
+        fs::write(&path, "apple\nbanana\ncherry\n").unwrap();
+        wait_for(|| watcher.reloads() == 1);
+
+        let after = watcher.checker();
+        assert!(after.contains_word("banana"));
+        // The configured settings survive the reload.
+        assert_eq!(after.correct_word("cherri"), "cherry");
+        assert_eq!(after.correct_word("chxrri"), "chxrri");
+        // Earlier snapshots keep the dictionary they were taken with.
+        assert!(!before.contains_word("banana"));
+    }
+
+    #[test]
+    fn a_failed_reload_keeps_the_previous_dictionary_and_reports_the_error() {
+        let path = file("reload_failure.txt", "apple\nbanana\n");
+        let (errors, failures) = mpsc::channel();
+        let watcher = DictionaryWatcher::watch(
+            &path,
+            LoadOptions::default(),
+            INTERVAL,
+            |checker| checker,
+            move |e| {
+                let _ = errors.send(e.clone());
+            },
+        )
+        .unwrap();
+
+        // Momentarily empty, as if caught mid-write.
+        fs::write(&path, "").unwrap();
+        let error = failures.recv_timeout(TIMEOUT).unwrap();
+
+        assert_eq!(error, SpellError::EmptyDictionary);
+        assert_eq!(watcher.reloads(), 0);
+        assert!(watcher.checker().contains_word("banana"));
+
+        fs::write(&path, "cherry\n").unwrap();
+        wait_for(|| watcher.reloads() == 1);
+        assert!(watcher.checker().contains_word("cherry"));
+        assert!(!watcher.checker().contains_word("banana"));
+    }
+}
@@ -0,0 +1,988 @@
+mod common;
+
+mod bom {
+    use homework_03::{ParseOptions, SpellChecker, WordList, WriteOptions, write_corrected_file};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn a_bom_prefixed_dictionary_keeps_its_first_word_clean() {
+        let checker =
+            SpellChecker::new(file("bom_dictionary.txt", "\u{feff}apple\nbanana\n")).unwrap();
+
+        assert!(checker.contains_word("apple"));
+        assert_eq!(checker.words_with_prefix("a"), ["apple"]);
+        assert_eq!(checker.correct_word("aple"), "apple");
+    }
+
+    #[test]
+    fn a_bom_prefixed_input_parses_its_first_line() {
+        let path = file("bom_input.txt", "\u{feff}0001 aple\n0002 banana\n");
+        let word_lists = WordList::read_and_get(&path, &ParseOptions::default()).unwrap();
+
+        assert_eq!(word_lists.len(), 2);
+        assert_eq!(word_lists[0].id, "0001");
+        assert_eq!(word_lists[0].line_number, 1);
+    }
+
+    #[test]
+    fn the_bom_does_not_leak_into_the_output() {
+        let checker =
+            SpellChecker::new(file("bom_dictionary_2.txt", "\u{feff}apple\nbanana\n")).unwrap();
+        let input = file("bom_input_2.txt", "\u{feff}0001 aple\n0002 banan\n");
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("bom_output.txt");
+
+        let word_lists = WordList::read_and_get(&input, &ParseOptions::default()).unwrap();
+        write_corrected_file(
+            &checker.correct_all(&word_lists),
+            &output,
+            &WriteOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "0001 apple\n0002 banana\n"
+        );
+    }
+}
+
+mod in_place {
+    use crate::common;
+    use homework_03::{
+        Encoding, ParseOptions, SpellChecker, WordList, WriteOptions, write_corrected_file,
+    };
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn checker() -> SpellChecker {
+        common::checker(&["quick", "the"]).with_max_distance(2)
+    }
+
+    fn input(name: &str, content: &str) -> PathBuf {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn correct_in_place(path: &PathBuf, options: &WriteOptions) -> Result<(), String> {
+        let corrected =
+            checker().correct_all(&WordList::read_and_get(path, &ParseOptions::default()).unwrap());
+        write_corrected_file(&corrected, path, options).map_err(|e| e.to_string())
+    }
+
+    fn leftovers(name: &str) -> bool {
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+            .join(format!(".{}.tmp", name))
+            .exists()
+    }
+
+    #[test]
+    fn the_input_is_replaced_with_its_corrections() {
+        let path = input("in_place_ok.txt", "0001 teh quikc\n0002 the\n");
+        let options = WriteOptions {
+            atomic: true,
+            ..WriteOptions::default()
+        };
+
+        correct_in_place(&path, &options).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "0001 the quick\n0002 the\n"
+        );
+        assert!(!leftovers("in_place_ok.txt"));
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_input_untouched() {
+        let original = "0001 teh \u{2603}\n";
+        let path = input("in_place_fail.txt", original);
+        let options = WriteOptions {
+            atomic: true,
+            encoding: Encoding::Latin1,
+            ..WriteOptions::default()
+        };
+
+        assert!(correct_in_place(&path, &options).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(!leftovers("in_place_fail.txt"));
+    }
+
+    #[test]
+    fn a_failed_rename_removes_the_temporary_file() {
+        let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("in_place_dir");
+        fs::create_dir_all(dir.join("occupied")).unwrap();
+        fs::write(dir.join("occupied").join("keep.txt"), "kept").unwrap();
+        let options = WriteOptions {
+            atomic: true,
+            ..WriteOptions::default()
+        };
+
+        let list = WordList::parse_line(1, "0001 the", &ParseOptions::default()).unwrap();
+        assert!(write_corrected_file(&[list], dir.join("occupied"), &options).is_err());
+        assert!(dir.join("occupied").join("keep.txt").exists());
+        assert!(!dir.join(".occupied.tmp").exists());
+    }
+}
+
+mod interrupted {
+    use crate::common;
+    use homework_03::{ParseOptions, WordList, WriteOptions, correct_stream, write_corrected_file};
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Barrier;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const INPUT: &str = "0001 aple  banana\n0002 chery/dat\n0003 eldr\n";
+
+    fn word_lists() -> Vec<WordList> {
+        WordList::parse_content(INPUT, &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn a_cancelled_run_passes_every_entry_through_as_written() {
+        let checker = common::checker(&["apple", "banana", "cherry", "date", "elder"]);
+        let word_lists = word_lists();
+
+        for jobs in [1, 2, 8] {
+            let run =
+                checker.correct_all_tracked(&word_lists, jobs, |_| true, &AtomicBool::new(true));
+
+            assert_eq!(run.completed(), 0);
+            assert_eq!(run.results.len(), word_lists.len());
+            assert!(run.results.iter().all(|(_, changes)| changes.is_empty()));
+            let written: Vec<String> = run
+                .results
+                .iter()
+                .map(|(list, _)| list.to_string())
+                .collect();
+            assert_eq!(written, INPUT.lines().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn a_cancelled_in_place_run_rewrites_the_file_unchanged() {
+        let checker = common::checker(&["apple", "banana", "cherry", "date", "elder"]);
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("interrupted_in_place.txt");
+        fs::write(&path, INPUT).unwrap();
+
+        let word_lists = WordList::read_and_get(&path, &ParseOptions::default()).unwrap();
+        let corrected: Vec<WordList> = checker
+            .correct_all_tracked(&word_lists, 2, |_| true, &AtomicBool::new(true))
+            .results
+            .into_iter()
+            .map(|(list, _)| list)
+            .collect();
+        let options = WriteOptions {
+            atomic: true,
+            ..WriteOptions::default()
+        };
+        write_corrected_file(&corrected, &path, &options).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), INPUT);
+    }
+
+    #[test]
+    fn a_cancelled_stream_copies_the_input_through() {
+        let checker = common::checker(&["apple", "banana", "cherry", "date", "elder"]);
+        let input = "0001 aple\n\nnot an entry\n0002 eldr\n";
+        let mut output = Vec::new();
+
+        let stats = correct_stream(
+            &checker,
+            input.as_bytes(),
+            &mut output,
+            &ParseOptions::default(),
+            &AtomicBool::new(true),
+        )
+        .unwrap();
+
+        assert!(stats.interrupted);
+        assert_eq!(stats.lines, 0);
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+
+    #[test]
+    fn entries_after_a_cancellation_follow_the_corrected_prefix_as_written() {
+        let checker = common::checker(&["apple", "banana", "cherry", "date", "elder"]);
+        let word_lists = word_lists();
+        let cancel = AtomicBool::new(false);
+
+        // The first entry is selected, then Ctrl-C arrives before the second.
+        let run = checker.correct_all_tracked(
+            &word_lists,
+            1,
+            |_| !cancel.swap(true, Ordering::Relaxed),
+            &cancel,
+        );
+
+        let written: Vec<String> = run
+            .results
+            .iter()
+            .map(|(list, _)| list.to_string())
+            .collect();
+        assert_eq!(
+            written,
+            ["0001 apple  banana", "0002 chery/dat", "0003 eldr"]
+        );
+        assert_eq!(
+            run.results[0].1,
+            [("aple".to_string(), "apple".to_string())]
+        );
+        assert_eq!(run.finished, [true, false, false]);
+    }
+
+    #[test]
+    fn chunks_keep_the_entries_they_finished() {
+        let checker = common::checker(&["apple", "banana", "cherry", "date", "elder"]);
+        let content = "0001 aple\n0002 chery\n0003 dat\n0004 eldr\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+        let cancel = AtomicBool::new(false);
+        let both_started = Barrier::new(2);
+
+        // Two chunks of two entries. Both chunks start their first entry, then
+        // the flag is raised before either reaches its second.
+        let run = checker.correct_all_tracked(
+            &word_lists,
+            2,
+            |_| {
+                both_started.wait();
+                cancel.store(true, Ordering::Relaxed);
+                true
+            },
+            &cancel,
+        );
+
+        let written: Vec<String> = run
+            .results
+            .iter()
+            .map(|(list, _)| list.to_string())
+            .collect();
+        assert_eq!(
+            written,
+            ["0001 apple", "0002 chery", "0003 date", "0004 eldr"]
+        );
+        assert_eq!(run.finished, [true, false, true, false]);
+        assert_eq!(run.completed(), 2);
+    }
+}
+
+mod latin1 {
+    use crate::common;
+    use homework_03::{
+        Encoding, ParseOptions, SpellError, WordList, WriteOptions, write_corrected_file,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+    }
+
+    fn latin1_parse_options() -> ParseOptions {
+        ParseOptions {
+            encoding: Encoding::Latin1,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn latin1_write_options() -> WriteOptions {
+        WriteOptions {
+            encoding: Encoding::Latin1,
+            ..WriteOptions::default()
+        }
+    }
+
+    #[test]
+    fn every_byte_round_trips_through_latin1() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let text = Encoding::Latin1.decode(bytes.clone()).unwrap();
+
+        assert_eq!(text.chars().count(), 256);
+        assert_eq!(Encoding::Latin1.encode(text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn a_latin1_file_is_read_corrected_and_written_back_as_latin1() {
+        // "0001 caf\u{e9} na\u{ef}ve fiance\n" in ISO-8859-1.
+        let input = path("latin1_in.txt");
+        fs::write(&input, b"0001 caf\xe9 na\xefve fiance\n").unwrap();
+
+        let word_lists = WordList::read_and_get(&input, &latin1_parse_options()).unwrap();
+        assert_eq!(word_lists[0].to_string(), "0001 café naïve fiance");
+
+        let checker = common::checker(&["café", "naïve", "fiancé"]);
+        let output = path("latin1_out.txt");
+        write_corrected_file(
+            &checker.correct_all(&word_lists),
+            &output,
+            &latin1_write_options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read(&output).unwrap(),
+            b"0001 caf\xe9 na\xefve fianc\xe9\n"
+        );
+    }
+
+    #[test]
+    fn utf8_input_is_not_readable_as_latin1_text() {
+        let input = path("latin1_from_utf8.txt");
+        fs::write(&input, "0001 café\n").unwrap();
+
+        let word_lists = WordList::read_and_get(&input, &latin1_parse_options()).unwrap();
+        // The two UTF-8 bytes of 'é' decode as two Latin-1 characters.
+        assert_eq!(word_lists[0].to_string(), "0001 cafÃ©");
+    }
+
+    #[test]
+    fn a_non_representable_character_fails_cleanly_without_writing() {
+        let word_lists =
+            WordList::parse_content("0001 5€ café\n", &ParseOptions::default()).unwrap();
+        let output = path("latin1_euro.txt");
+        let _ = fs::remove_file(&output);
+
+        let error =
+            write_corrected_file(&word_lists, &output, &latin1_write_options()).unwrap_err();
+
+        match error {
+            SpellError::Encoding { encoding, reason } => {
+                assert_eq!(encoding, "ISO-8859-1");
+                assert!(reason.contains("U+20AC"), "{}", reason);
+            }
+            other => panic!("expected an encoding error, got {:?}", other),
+        }
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn utf8_stays_the_default() {
+        let word_lists =
+            WordList::parse_content("0001 5€ café\n", &ParseOptions::default()).unwrap();
+        let output = path("latin1_default.txt");
+
+        write_corrected_file(&word_lists, &output, &WriteOptions::default()).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "0001 5€ café\n");
+    }
+}
+
+mod learned {
+    use crate::common;
+    use homework_03::LearnedCorrections;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn store_path(name: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR"))
+            .join("learned")
+            .join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    const WORDS: &[&str] = &["their", "there", "the"];
+
+    #[test]
+    fn a_missing_file_starts_an_empty_store() {
+        let learned = LearnedCorrections::open(store_path("missing.txt")).unwrap();
+        assert!(learned.is_empty());
+    }
+
+    #[test]
+    fn confirmed_pairs_survive_a_save_and_reload() {
+        let path = store_path("persist.txt");
+        let mut learned = LearnedCorrections::open(&path).unwrap();
+        assert!(learned.confirm("thier", "their"));
+        assert!(learned.confirm("teh", "the"));
+        assert!(learned.confirm("thier", "there"));
+        assert!(!learned.confirm("the", "the"));
+        assert!(!learned.confirm("a b", "ab"));
+        learned.save().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "teh the\nthier there\n");
+        let reloaded = LearnedCorrections::open(&path).unwrap();
+        assert_eq!(reloaded.pairs(), learned.pairs());
+    }
+
+    #[test]
+    fn learned_corrections_win_over_the_dictionary_search() {
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.correct_word("thier"), "the");
+
+        let checker =
+            checker.with_learned(HashMap::from([("thier".to_string(), "their".to_string())]));
+        assert_eq!(checker.correct_word("thier"), "their");
+    }
+
+    #[test]
+    fn forced_corrections_win_over_learned_ones() {
+        let checker = common::checker(WORDS)
+            .with_learned(HashMap::from([("thier".to_string(), "there".to_string())]))
+            .with_forced(HashMap::from([("thier".to_string(), "the".to_string())]));
+        assert_eq!(checker.correct_word("thier"), "the");
+    }
+
+    #[test]
+    fn only_accepted_corrections_are_learned() {
+        let mut learned = LearnedCorrections::open(store_path("accepted.txt")).unwrap();
+        let corrections = [
+            ("thier", "the"),
+            ("teh", "the"),
+            ("thier", "the"),
+            ("the", "the"),
+        ];
+
+        let mut offered = Vec::new();
+        let recorded = learned.confirm_with(corrections, |original, corrected| {
+            offered.push((original.to_string(), corrected.to_string()));
+            original == "teh"
+        });
+
+        assert_eq!(recorded, 1);
+        assert_eq!(
+            offered,
+            [
+                ("thier".to_string(), "the".to_string()),
+                ("teh".to_string(), "the".to_string()),
+            ]
+        );
+        assert_eq!(learned.pairs().get("teh").map(String::as_str), Some("the"));
+        assert!(!learned.pairs().contains_key("thier"));
+    }
+
+    #[test]
+    fn corrections_already_learned_are_not_offered_again() {
+        let mut learned = LearnedCorrections::open(store_path("known.txt")).unwrap();
+        learned.confirm("teh", "the");
+
+        let recorded = learned.confirm_with([("teh", "the")], |_, _| panic!("offered again"));
+        assert_eq!(recorded, 0);
+    }
+}
+
+mod observed {
+    use crate::common;
+    use homework_03::{ObservedFrequencies, ParseOptions, SpellChecker, WordList};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn store_path(name: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR"))
+            .join("observed")
+            .join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    const WORDS: &[&str] = &["cart", "cat", "coat"];
+
+    fn corrected(checker: &SpellChecker, line: &str) -> WordList {
+        let word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+        checker.correct_word_list(&word_list)
+    }
+
+    #[test]
+    fn a_missing_file_starts_an_empty_store() {
+        assert!(
+            ObservedFrequencies::open(store_path("missing.txt"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn only_known_output_words_are_counted() {
+        let checker = common::checker(WORDS).with_max_distance(1);
+        let mut observed = ObservedFrequencies::open(store_path("known.txt")).unwrap();
+
+        let word_list = corrected(&checker, "0001 coat coat cta xyzzy");
+        assert_eq!(observed.observe_word_list(&word_list, &checker), 2);
+        assert_eq!(observed.counts().get("coat"), Some(&2));
+        assert_eq!(observed.len(), 1);
+    }
+
+    #[test]
+    fn counts_accumulate_across_runs() {
+        let path = store_path("accumulate.txt");
+        let checker = common::checker(WORDS);
+
+        for _ in 0..2 {
+            let mut observed = ObservedFrequencies::open(&path).unwrap();
+            observed.observe_word_list(&corrected(&checker, "0001 coat cart coat"), &checker);
+            observed.save().unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "cart 2\ncoat 4\n");
+        let reloaded = ObservedFrequencies::open(&path).unwrap();
+        assert_eq!(reloaded.counts().get("coat"), Some(&4));
+    }
+
+    #[test]
+    fn reloaded_counts_steer_the_tie_break() {
+        let path = store_path("boost.txt");
+        let checker = common::checker(WORDS);
+        assert_eq!(checker.correct_word("caat"), "cart");
+
+        let mut observed = ObservedFrequencies::open(&path).unwrap();
+        observed.observe_word_list(&corrected(&checker, "0001 coat"), &checker);
+        observed.save().unwrap();
+
+        let reloaded = ObservedFrequencies::open(&path).unwrap();
+        let boosted = checker.with_frequencies(reloaded.counts().clone());
+        assert_eq!(boosted.correct_word("caat"), "coat");
+    }
+
+    #[test]
+    fn words_with_whitespace_are_not_counted() {
+        let mut observed = ObservedFrequencies::open(store_path("whitespace.txt")).unwrap();
+        assert!(!observed.observe("ice cream"));
+        assert!(observed.observe("ice"));
+    }
+}
+
+mod paths {
+    use homework_03::{ParseOptions, WordList, WriteOptions, same_file, write_corrected_file};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn tmp() -> PathBuf {
+        Path::new(env!("CARGO_TARGET_TMPDIR")).join("paths")
+    }
+
+    fn lists() -> Vec<WordList> {
+        WordList::parse_content("0001 apple\n", &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn missing_parent_directories_are_created() {
+        let output = tmp().join("nested").join("deeper").join("out.txt");
+        let _ = fs::remove_dir_all(tmp().join("nested"));
+
+        write_corrected_file(&lists(), &output, &WriteOptions::default()).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "0001 apple\n");
+    }
+
+    #[test]
+    fn crlf_input_parses_like_lf_input() {
+        let options = ParseOptions::default();
+        let crlf = WordList::parse_content("0001 apple pie\r\n0002 tart\r\n", &options).unwrap();
+        let lf = WordList::parse_content("0001 apple pie\n0002 tart\n", &options).unwrap();
+
+        assert_eq!(crlf.len(), 2);
+        for (crlf, lf) in crlf.iter().zip(&lf) {
+            assert_eq!(crlf.tokens, lf.tokens);
+        }
+    }
+
+    #[test]
+    fn same_file_sees_through_dot_segments() {
+        let dir = tmp().join("same");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("words.txt"), "0001 apple\n").unwrap();
+
+        assert!(same_file(
+            dir.join("words.txt"),
+            dir.join(".").join("..").join("same").join("words.txt")
+        ));
+        assert!(!same_file(dir.join("words.txt"), dir.join("other.txt")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn backslash_paths_are_joined_and_created() {
+        let output = PathBuf::from(format!("{}\\win\\nested\\out.txt", tmp().display()));
+        write_corrected_file(&lists(), &output, &WriteOptions::default()).unwrap();
+        assert!(same_file(
+            &output,
+            tmp().join("win").join("nested").join("out.txt")
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn drive_letter_and_unc_paths_keep_their_prefix() {
+        assert_eq!(
+            Path::new(r"C:\data\words.txt").parent(),
+            Some(Path::new(r"C:\data"))
+        );
+        assert_eq!(
+            Path::new(r"\\server\share\words.txt").parent(),
+            Some(Path::new(r"\\server\share\"))
+        );
+    }
+}
+
+mod problem_files {
+    use homework_03::{ParseOptions, SpellChecker, WordList, WriteOptions, write_corrected_file};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn problem_file(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("problem")
+            .join(name)
+    }
+
+    #[test]
+    fn bundled_problem_files_correct_end_to_end() {
+        let checker = SpellChecker::new(problem_file("vocabulary.txt")).unwrap();
+        let options = ParseOptions::default();
+        let word_lists = WordList::read_and_get(problem_file("words.txt"), &options).unwrap();
+
+        let corrected: Vec<WordList> = word_lists
+            .iter()
+            .map(|word_list| checker.correct_word_list(word_list))
+            .collect();
+
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("problem_files_output.txt");
+        write_corrected_file(&corrected, &output, &WriteOptions::default()).unwrap();
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(!content.is_empty());
+
+        // The output must be valid input again, one entry per input entry with
+        // the IDs in the same order.
+        let reparsed = WordList::parse_content(&content, &options).unwrap();
+        assert_eq!(reparsed.len(), word_lists.len());
+        for (original, written) in word_lists.iter().zip(&reparsed) {
+            assert_eq!(written.id, original.id);
+        }
+    }
+}
+
+mod routing {
+    use homework_03::{ParseOptions, WordList, WriteOptions, write_routed_files};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn output_dir(name: &str) -> PathBuf {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn word_lists() -> Vec<WordList> {
+        let content = "0001 apple\n1002 banana\n0003 cherry\n2004 date\n1005 elder\n";
+        WordList::parse_content(content, &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn lines_are_routed_by_id_prefix_in_input_order() {
+        let dir = output_dir("routing_prefixes");
+        let rules = vec![
+            ("0".to_string(), dir.join("zero.txt")),
+            ("1".to_string(), dir.join("one.txt")),
+        ];
+        let default = dir.join("other.txt");
+
+        let written =
+            write_routed_files(&word_lists(), &rules, &default, &WriteOptions::default()).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                (dir.join("zero.txt"), 2),
+                (dir.join("one.txt"), 2),
+                (default.clone(), 1),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("zero.txt")).unwrap(),
+            "0001 apple\n0003 cherry\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("one.txt")).unwrap(),
+            "1002 banana\n1005 elder\n"
+        );
+        assert_eq!(fs::read_to_string(default).unwrap(), "2004 date\n");
+    }
+
+    #[test]
+    fn the_first_matching_rule_wins() {
+        let dir = output_dir("routing_first_match");
+        let rules = vec![
+            ("100".to_string(), dir.join("narrow.txt")),
+            ("1".to_string(), dir.join("wide.txt")),
+        ];
+        let default = dir.join("other.txt");
+
+        write_routed_files(&word_lists(), &rules, &default, &WriteOptions::default()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("narrow.txt")).unwrap(),
+            "1002 banana\n1005 elder\n"
+        );
+        assert_eq!(fs::read_to_string(dir.join("wide.txt")).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(default).unwrap(),
+            "0001 apple\n0003 cherry\n2004 date\n"
+        );
+    }
+}
+
+mod same_file {
+    use homework_03::same_file;
+    use std::path::Path;
+
+    fn problem() -> &'static Path {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/problem"))
+    }
+
+    #[test]
+    fn the_same_path_twice_is_the_same_file() {
+        let words = problem().join("words.txt");
+        assert!(same_file(&words, &words));
+    }
+
+    #[test]
+    fn differently_spelled_paths_to_one_file_match() {
+        let words = problem().join("words.txt");
+        let roundabout = problem().join("..").join("problem").join("words.txt");
+        assert!(same_file(&words, roundabout));
+    }
+
+    #[test]
+    fn different_files_do_not_match() {
+        assert!(!same_file(
+            problem().join("words.txt"),
+            problem().join("vocabulary.txt")
+        ));
+    }
+
+    #[test]
+    fn missing_files_are_compared_as_given() {
+        let missing = problem().join("missing.txt");
+        assert!(same_file(&missing, &missing));
+        assert!(!same_file(&missing, problem().join("also_missing.txt")));
+    }
+}
+
+mod snapshot {
+    use homework_03::{ParseOptions, SpellChecker, WordList, WriteOptions, write_corrected_file};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
+
+    fn run_pipeline(name: &str) -> String {
+        let dir = fixture(name);
+        let checker = SpellChecker::new(dir.join("vocabulary.txt")).unwrap();
+        let word_lists =
+            WordList::read_and_get(dir.join("words.txt"), &ParseOptions::default()).unwrap();
+
+        let corrected: Vec<WordList> = word_lists
+            .iter()
+            .map(|word_list| checker.correct_word_list(word_list))
+            .collect();
+
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("snapshot_{}.txt", name));
+        write_corrected_file(&corrected, &output, &WriteOptions::default()).unwrap();
+        fs::read_to_string(output).unwrap()
+    }
+
+    fn assert_snapshot(name: &str) {
+        let expected = fs::read_to_string(fixture(name).join("expected.txt")).unwrap();
+        assert_eq!(run_pipeline(name), expected);
+    }
+
+    #[test]
+    fn output_matches_snapshot_when_no_correction_is_needed() {
+        assert_snapshot("clean");
+    }
+
+    #[test]
+    fn output_matches_snapshot_when_every_word_is_corrected() {
+        assert_snapshot("all_corrected");
+    }
+}
+
+mod trailing_newline {
+    use homework_03::{
+        ParseOptions, WordList, WriteOptions, ends_with_newline, write_corrected_file,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn file(name: &str, content: &str) -> PathBuf {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Writes `input` back with the trailing newline policy read from it.
+    fn round_trip(name: &str, input: &str) -> String {
+        let input_path = file(&format!("{}_in.txt", name), input);
+        let output_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}_out.txt", name));
+        let options = WriteOptions {
+            trailing_newline: ends_with_newline(&input_path).unwrap(),
+            ..WriteOptions::default()
+        };
+
+        let lists = WordList::read_and_get(&input_path, &ParseOptions::default()).unwrap();
+        write_corrected_file(&lists, &output_path, &options).unwrap();
+        fs::read_to_string(output_path).unwrap()
+    }
+
+    #[test]
+    fn the_final_newline_is_detected() {
+        assert!(ends_with_newline(file("newline_yes.txt", "0001 a\n")).unwrap());
+        assert!(!ends_with_newline(file("newline_no.txt", "0001 a")).unwrap());
+        assert!(!ends_with_newline(file("newline_empty.txt", "")).unwrap());
+    }
+
+    #[test]
+    fn input_with_a_trailing_newline_keeps_it() {
+        let input = "0001 apple\n0002 pie\n";
+        assert_eq!(round_trip("with_newline", input), input);
+    }
+
+    #[test]
+    fn input_without_a_trailing_newline_stays_without() {
+        let input = "0001 apple\n0002 pie";
+        assert_eq!(round_trip("without_newline", input), input);
+    }
+
+    #[test]
+    fn the_default_policy_writes_a_trailing_newline() {
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("newline_default.txt");
+        let lists = WordList::parse_content("0001 apple", &ParseOptions::default()).unwrap();
+        write_corrected_file(&lists, &output, &WriteOptions::default()).unwrap();
+        assert_eq!(fs::read_to_string(output).unwrap(), "0001 apple\n");
+    }
+}
+
+mod trailing_separator {
+    use homework_03::{ParseOptions, Token, WordList, WriteOptions, write_corrected_file};
+    use std::fs;
+    use std::path::Path;
+
+    fn parse(line: &str) -> WordList {
+        WordList::parse_line(1, line, &ParseOptions::default()).unwrap()
+    }
+
+    fn trimmed(line: &str) -> String {
+        let mut word_list = parse(line);
+        word_list.trim_trailing_separators();
+        word_list.to_string()
+    }
+
+    #[test]
+    fn trims_a_trailing_space() {
+        assert_eq!(trimmed("0001 the quick "), "0001 the quick");
+    }
+
+    #[test]
+    fn trims_a_trailing_slash() {
+        assert_eq!(trimmed("0001 and/or/"), "0001 and/or");
+    }
+
+    #[test]
+    fn trims_a_whole_trailing_run_but_keeps_inner_separators() {
+        assert_eq!(trimmed("0001 the  quick / "), "0001 the  quick");
+    }
+
+    #[test]
+    fn writing_trims_only_when_asked() {
+        let lists = vec![parse("0001 and/or/"), parse("0002 the quick ")];
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("trailing_separator.txt");
+
+        write_corrected_file(&lists, &output, &WriteOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "0001 and/or/\n0002 the quick \n"
+        );
+
+        let options = WriteOptions {
+            trim_trailing_separators: true,
+            ..WriteOptions::default()
+        };
+        write_corrected_file(&lists, &output, &options).unwrap();
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "0001 and/or\n0002 the quick\n"
+        );
+    }
+
+    #[test]
+    fn trailing_spaces_of_parsed_lines_are_separators() {
+        let content = "0001 the quick \n0002 and/or/ \t\n";
+        let mut lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+        assert_eq!(lists[0].tokens.last(), Some(&Token::Separator(' ')));
+        assert_eq!(lists[1].trailing, "\t");
+
+        for word_list in &mut lists {
+            word_list.trim_trailing_separators();
+        }
+        assert_eq!(lists[0].to_string(), "0001 the quick");
+        assert_eq!(lists[1].to_string(), "0002 and/or");
+    }
+}
+
+mod words_only {
+    use crate::common;
+    use homework_03::{ParseOptions, WordList, WordsOnly, WriteOptions, write_corrected_file};
+    use std::fs;
+    use std::path::Path;
+
+    fn lists() -> Vec<WordList> {
+        let options = ParseOptions {
+            delimiters: vec!["::".to_string()],
+            ..ParseOptions::default()
+        };
+        let checker = common::checker(&["and", "or", "quick", "the"]);
+        ["0001 teh  quikc ", "0002 and/or::teh"]
+            .into_iter()
+            .map(|line| {
+                checker.correct_word_list(&WordList::parse_line(1, line, &options).unwrap())
+            })
+            .collect()
+    }
+
+    fn written(name: &str, words_only: WordsOnly) -> String {
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        let options = WriteOptions {
+            words_only: Some(words_only),
+            ..WriteOptions::default()
+        };
+        write_corrected_file(&lists(), &output, &options).unwrap();
+        fs::read_to_string(&output).unwrap()
+    }
+
+    #[test]
+    fn joined_words_drop_ids_and_separators() {
+        assert_eq!(
+            written("words_only_joined.txt", WordsOnly::Joined),
+            "the quick\nand or the\n"
+        );
+    }
+
+    #[test]
+    fn one_word_per_line() {
+        assert_eq!(
+            written("words_only_per_line.txt", WordsOnly::PerLine),
+            "the\nquick\nand\nor\nthe\n"
+        );
+    }
+
+    #[test]
+    fn full_lines_by_default() {
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("words_only_default.txt");
+        write_corrected_file(&lists(), &output, &WriteOptions::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "0001 the  quick \n0002 and/or::the\n"
+        );
+    }
+}
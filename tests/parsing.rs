@@ -0,0 +1,1032 @@
+mod common;
+
+mod control_chars {
+    use homework_03::{
+        ControlCharPolicy, Dictionary, LoadOptions, ParseOptions, SpellError, Token, WordList,
+    };
+
+    fn load(content: &str, policy: ControlCharPolicy) -> Dictionary {
+        let options = LoadOptions {
+            control_chars: policy,
+            ..LoadOptions::default()
+        };
+        Dictionary::from_content(content, &options).unwrap()
+    }
+
+    fn parse(line: &str, policy: ControlCharPolicy) -> Result<Vec<WordList>, SpellError> {
+        let options = ParseOptions {
+            control_chars: policy,
+            ..ParseOptions::default()
+        };
+        WordList::parse_content(line, &options)
+    }
+
+    fn words(word_list: &WordList) -> Vec<&str> {
+        word_list
+            .tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Word(word) => Some(word.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const DICTIONARY: &str = "apple\nba\0nana\ncher\x0cry\ndate\n";
+
+    #[test]
+    fn the_policy_applies_to_one_piece_of_text() {
+        let text = "wo\0rd";
+        assert_eq!(
+            ControlCharPolicy::Strip.apply(text),
+            (Some("word".to_string()), true)
+        );
+        assert_eq!(ControlCharPolicy::Reject.apply(text), (None, true));
+        assert_eq!(
+            ControlCharPolicy::Keep.apply(text),
+            (Some(text.to_string()), false)
+        );
+        assert_eq!(
+            ControlCharPolicy::Reject.apply("word"),
+            (Some("word".to_string()), false)
+        );
+    }
+
+    #[test]
+    fn stripping_dictionary_entries_keeps_the_rest_and_counts_them() {
+        let dictionary = load(DICTIONARY, ControlCharPolicy::Strip);
+        assert_eq!(dictionary.words(), ["apple", "banana", "cherry", "date"]);
+        assert_eq!(dictionary.sanitized_entries(), 2);
+    }
+
+    #[test]
+    fn rejecting_dictionary_entries_drops_them_and_counts_them() {
+        let dictionary = load(DICTIONARY, ControlCharPolicy::Reject);
+        assert_eq!(dictionary.words(), ["apple", "date"]);
+        assert_eq!(dictionary.sanitized_entries(), 2);
+    }
+
+    #[test]
+    fn keeping_dictionary_entries_leaves_them_untouched() {
+        let dictionary = load(DICTIONARY, ControlCharPolicy::Keep);
+        assert_eq!(
+            dictionary.words(),
+            ["apple", "ba\0nana", "cher\x0cry", "date"]
+        );
+        assert_eq!(dictionary.sanitized_entries(), 0);
+    }
+
+    #[test]
+    fn words_built_in_memory_are_stripped_by_default() {
+        let dictionary =
+            Dictionary::from_words(["ap\0ple".to_string(), "\x07".to_string()]).unwrap();
+        assert_eq!(dictionary.words(), ["apple"]);
+        assert_eq!(dictionary.sanitized_entries(), 2);
+    }
+
+    #[test]
+    fn stripping_input_tokens_removes_the_control_characters() {
+        let word_lists = parse("0001 ap\0ple ba\x0cnana\n", ControlCharPolicy::Strip).unwrap();
+        assert_eq!(words(&word_lists[0]), ["apple", "banana"]);
+    }
+
+    #[test]
+    fn rejecting_input_tokens_fails_the_line() {
+        let error = parse("0001 apple\n0002 ba\0nana\n", ControlCharPolicy::Reject).unwrap_err();
+        assert!(matches!(error, SpellError::InvalidLine { line: 2, .. }));
+    }
+
+    #[test]
+    fn keeping_input_tokens_passes_them_through() {
+        let word_lists = parse("0001 ba\0nana\n", ControlCharPolicy::Keep).unwrap();
+        assert_eq!(words(&word_lists[0]), ["ba\0nana"]);
+    }
+}
+
+mod dedup_words {
+    use homework_03::{ParseOptions, WordList};
+
+    fn dedup(line: &str) -> (String, usize) {
+        let mut word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+        let removed = word_list.dedup_words();
+        (word_list.to_string(), removed)
+    }
+
+    #[test]
+    fn removes_a_doubled_word_and_its_separator() {
+        assert_eq!(
+            dedup("0001 the the quick"),
+            ("0001 the quick".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn ignores_case_and_keeps_the_first_spelling() {
+        assert_eq!(
+            dedup("0001 The the quick"),
+            ("0001 The quick".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn collapses_longer_runs_and_keeps_trailing_separators() {
+        assert_eq!(
+            dedup("0001 quick the  the/the/ fox"),
+            ("0001 quick the/ fox".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn removes_a_doubled_word_at_the_end_of_the_line() {
+        assert_eq!(dedup("0001 fox the the"), ("0001 fox the".to_string(), 1));
+    }
+
+    #[test]
+    fn leaves_words_that_are_not_adjacent() {
+        assert_eq!(
+            dedup("0001 the fox the dog"),
+            ("0001 the fox the dog".to_string(), 0)
+        );
+    }
+}
+
+mod delimiters {
+    use crate::common;
+    use homework_03::{ParseOptions, Token, WordList};
+
+    fn options(delimiters: &[&str]) -> ParseOptions {
+        ParseOptions {
+            delimiters: delimiters.iter().map(|d| d.to_string()).collect(),
+            ..ParseOptions::default()
+        }
+    }
+
+    fn word(w: &str) -> Token {
+        Token::Word(w.to_string())
+    }
+
+    fn delimiter(d: &str) -> Token {
+        Token::Delimiter(d.to_string())
+    }
+
+    #[test]
+    fn double_colon_splits_words() {
+        let tokens = WordList::parse_tokens("apple::pie::tart", &options(&["::"]));
+        assert_eq!(
+            tokens,
+            vec![
+                word("apple"),
+                delimiter("::"),
+                word("pie"),
+                delimiter("::"),
+                word("tart")
+            ]
+        );
+    }
+
+    #[test]
+    fn spaced_pipe_wins_over_the_space_separator() {
+        let tokens = WordList::parse_tokens("apple pie | tart", &options(&[" | "]));
+        assert_eq!(
+            tokens,
+            vec![
+                word("apple"),
+                Token::Separator(' '),
+                word("pie"),
+                delimiter(" | "),
+                word("tart")
+            ]
+        );
+    }
+
+    #[test]
+    fn the_longest_matching_delimiter_wins() {
+        let tokens = WordList::parse_tokens("a:::b", &options(&[":", "::", ":::"]));
+        assert_eq!(tokens, vec![word("a"), delimiter(":::"), word("b")]);
+    }
+
+    #[test]
+    fn single_characters_split_as_before_without_delimiters() {
+        let tokens = WordList::parse_tokens("a::b", &ParseOptions::default());
+        assert_eq!(tokens, vec![word("a::b")]);
+    }
+
+    #[test]
+    fn delimiters_survive_correction() {
+        let checker = common::checker(&["apple", "pie", "tart"]);
+        let line = "0001 aple::pie | trat";
+        let word_list = WordList::parse_line(1, line, &options(&["::", " | "])).unwrap();
+        assert_eq!(
+            checker.correct_word_list(&word_list).to_string(),
+            "0001 apple::pie | tart"
+        );
+    }
+}
+
+mod duplicate_ids {
+    use homework_03::{ParseOptions, WordList};
+
+    #[test]
+    fn reports_lines_sharing_an_id() {
+        let content = "0001 apple\n0002 banana\n0001 cherry\n0003 date\n0002 elder\n0001 fig\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            WordList::duplicate_ids(&word_lists),
+            vec![("0001", vec![1, 3, 6]), ("0002", vec![2, 5])]
+        );
+    }
+
+    #[test]
+    fn unique_ids_report_nothing() {
+        let content = "0001 apple\n0002 banana\n";
+        let word_lists = WordList::parse_content(content, &ParseOptions::default()).unwrap();
+
+        assert!(WordList::duplicate_ids(&word_lists).is_empty());
+    }
+}
+
+mod freeform {
+    use crate::common;
+    use homework_03::{ParseOptions, Token, WordList};
+
+    const PROSE: &str = "The quick brwn fox\njumps over\r\n\nthe lazzy dog.\n";
+
+    const WORDS: &[&str] = &[
+        "The", "brown", "dog.", "fox", "jumps", "lazy", "over", "quick", "the",
+    ];
+
+    #[test]
+    fn line_breaks_are_separators() {
+        let document = WordList::parse_freeform(PROSE, &ParseOptions::default()).unwrap();
+        assert_eq!(document.id, "");
+        assert!(document.tokens.contains(&Token::Separator('\n')));
+        assert!(document.tokens.contains(&Token::Separator('\r')));
+        assert!(
+            document
+                .tokens
+                .iter()
+                .all(|token| !matches!(token, Token::Word(word) if word.contains('\n')))
+        );
+    }
+
+    #[test]
+    fn round_trip_keeps_the_layout() {
+        let document = WordList::parse_freeform(PROSE, &ParseOptions::default()).unwrap();
+        assert_eq!(document.to_string(), PROSE);
+
+        let corrected = common::checker(WORDS).correct_word_list(&document);
+        assert_eq!(
+            corrected.to_string(),
+            "The quick brown fox\njumps over\r\n\nthe lazy dog.\n"
+        );
+    }
+
+    #[test]
+    fn lines_without_an_id_are_accepted() {
+        assert!(WordList::parse_line(1, "jumps over", &ParseOptions::default()).is_err());
+        assert!(WordList::parse_freeform("jumps over", &ParseOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn text_without_words_is_rejected() {
+        assert!(WordList::parse_freeform("\n \n", &ParseOptions::default()).is_err());
+    }
+}
+
+mod hints {
+    use crate::common;
+    use homework_03::{ParseOptions, TokenOutcome, WordList};
+
+    fn options(open: &str, close: &str) -> ParseOptions {
+        ParseOptions {
+            hint_markers: Some((open.to_string(), close.to_string())),
+            ..ParseOptions::default()
+        }
+    }
+
+    const WORDS: &[&str] = &["cat", "the", "then"];
+
+    fn correct(line: &str, options: &ParseOptions) -> String {
+        let word_list = WordList::parse_line(1, line, options).unwrap();
+        common::checker(WORDS)
+            .correct_word_list(&word_list)
+            .to_string()
+    }
+
+    #[test]
+    fn hinted_tokens_take_the_hint() {
+        let options = options("{->", "}");
+        assert_eq!(correct("0001 teh{->then} catt", &options), "0001 then cat");
+    }
+
+    #[test]
+    fn the_hint_need_not_be_a_dictionary_word() {
+        let options = options("{->", "}");
+        assert_eq!(correct("0001 teh{->thy}", &options), "0001 thy");
+    }
+
+    #[test]
+    fn markup_is_stripped_while_parsing() {
+        let word_list =
+            WordList::parse_line(1, "0001 teh{->the} cat", &options("{->", "}")).unwrap();
+        assert_eq!(word_list.to_string(), "0001 teh cat");
+        assert_eq!(word_list.hints.get(&0).map(String::as_str), Some("the"));
+    }
+
+    #[test]
+    fn the_syntax_is_configurable() {
+        let options = options("[[", "]]");
+        assert_eq!(correct("0001 teh[[then]]", &options), "0001 then");
+        let word_list = WordList::parse_line(1, "0001 teh{->then}", &options).unwrap();
+        assert!(word_list.hints.is_empty());
+        assert_eq!(word_list.to_string(), "0001 teh{->then}");
+    }
+
+    #[test]
+    fn incomplete_markup_stays_in_the_word() {
+        let word_list =
+            WordList::parse_line(1, "0001 {->the} teh{->}", &options("{->", "}")).unwrap();
+        assert!(word_list.hints.is_empty());
+        assert_eq!(word_list.to_string(), "0001 {->the} teh{->}");
+    }
+
+    #[test]
+    fn hints_are_reported_as_corrections() {
+        let word_list = WordList::parse_line(1, "0001 teh{->the}", &options("{->", "}")).unwrap();
+        let (_, outcomes) = common::checker(WORDS).correct_word_list_detailed(&word_list);
+        assert_eq!(
+            outcomes,
+            [TokenOutcome::Corrected {
+                from: "teh".to_string(),
+                to: "the".to_string(),
+                distance: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn hints_are_off_by_default() {
+        let word_list =
+            WordList::parse_line(1, "0001 teh{->the}", &ParseOptions::default()).unwrap();
+        assert!(word_list.hints.is_empty());
+    }
+}
+
+mod id_separator {
+    use crate::common;
+    use homework_03::{ParseOptions, Token, WordList};
+
+    fn round_trip(line: &str) -> String {
+        WordList::parse_line(1, line, &ParseOptions::default())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn a_tab_after_the_id_is_reproduced() {
+        let line = "0001\tapple pie";
+        assert_eq!(round_trip(line), line);
+    }
+
+    #[test]
+    fn multiple_spaces_after_the_id_are_reproduced() {
+        let line = "0001   apple pie";
+        let word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+        assert_eq!(word_list.id_separator, "   ");
+        assert_eq!(word_list.to_string(), line);
+    }
+
+    #[test]
+    fn mixed_whitespace_after_the_id_survives_correction() {
+        let checker = common::checker(&["apple", "pie"]);
+        for line in ["0001 \t apple pie", "0002\t\tapple pie"] {
+            let word_list = WordList::parse_line(1, line, &ParseOptions::default()).unwrap();
+            assert_eq!(checker.correct_word_list(&word_list).to_string(), line);
+        }
+    }
+
+    #[test]
+    fn collapsing_writes_a_single_space() {
+        let options = ParseOptions {
+            collapse_id_separator: true,
+            ..ParseOptions::default()
+        };
+        let word_list = WordList::parse_line(1, "0001\t  apple", &options).unwrap();
+        assert_eq!(word_list.to_string(), "0001 apple");
+    }
+
+    #[test]
+    fn collapsing_keeps_the_spacing_between_words() {
+        let options = ParseOptions {
+            collapse_id_separator: true,
+            ..ParseOptions::default()
+        };
+        let word_list = WordList::parse_line(1, "0001    apple  pie", &options).unwrap();
+        assert_eq!(word_list.id_separator, " ");
+        assert_eq!(word_list.to_string(), "0001 apple  pie");
+    }
+
+    #[test]
+    fn without_collapsing_no_leading_separator_tokens_appear() {
+        let word_list = WordList::parse_line(1, "0001    apple", &ParseOptions::default()).unwrap();
+        assert_eq!(word_list.tokens, [Token::Word("apple".to_string())]);
+    }
+
+    #[test]
+    fn collapsed_lines_stay_collapsed_after_correction() {
+        let options = ParseOptions {
+            collapse_id_separator: true,
+            ..ParseOptions::default()
+        };
+        let checker = common::checker(&["apple", "pie"]);
+        let word_list = WordList::parse_line(1, "0001 \t aple pie", &options).unwrap();
+        assert_eq!(
+            checker.correct_word_list(&word_list).to_string(),
+            "0001 apple pie"
+        );
+    }
+
+    #[test]
+    fn untouched_lines_keep_their_surrounding_whitespace() {
+        let checker = common::checker(&["apple", "pie"]);
+        let content = "0001 apple pie \t\n  0002\tapple pie\n0003 aple pie  \n";
+        let corrected: Vec<String> = WordList::parse_content(content, &ParseOptions::default())
+            .unwrap()
+            .iter()
+            .map(|word_list| checker.correct_word_list(word_list).to_string())
+            .collect();
+
+        assert_eq!(
+            corrected,
+            ["0001 apple pie \t", "  0002\tapple pie", "0003 apple pie  "]
+        );
+    }
+}
+
+mod lenient {
+    use crate::common;
+    use homework_03::{ParseOptions, SpellError, WordList, correct_stream};
+    use std::sync::atomic::AtomicBool;
+
+    fn lenient(max_line_length: Option<usize>) -> ParseOptions {
+        ParseOptions {
+            lenient: true,
+            max_line_length,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn strict_parsing_stops_at_the_first_invalid_line() {
+        let result =
+            WordList::parse_content("0001 apple\nxx\n0002 pie\n", &ParseOptions::default());
+        assert!(matches!(
+            result,
+            Err(SpellError::InvalidLine { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn lenient_parsing_returns_the_skipped_lines() {
+        let (entries, skipped) = WordList::parse_content_with_skipped(
+            "0001 apple\nxx\n0002 pie\nabcd e\n",
+            &lenient(None),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        let lines: Vec<_> = skipped
+            .iter()
+            .map(|e| match e {
+                SpellError::InvalidLine { line, .. } => *line,
+                other => panic!("unexpected error: {}", other),
+            })
+            .collect();
+        assert_eq!(lines, [2, 4]);
+    }
+
+    #[test]
+    fn overlong_lines_are_skipped_in_lenient_mode() {
+        let (entries, skipped) = WordList::parse_content_with_skipped(
+            "0001 apple\n0002 a b c d e f\n",
+            &lenient(Some(12)),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            skipped,
+            [SpellError::LineTooLong {
+                line: 2,
+                length: 16,
+                max: 12
+            }]
+        );
+    }
+
+    #[test]
+    fn overlong_lines_fail_in_strict_mode() {
+        let options = ParseOptions {
+            max_line_length: Some(12),
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            WordList::parse_content("0001 a b c d e f\n", &options),
+            Err(SpellError::LineTooLong { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn skipped_lines_stay_out_of_streamed_output() {
+        let checker = common::checker(&["apple", "pie"]);
+        let mut output = Vec::new();
+
+        let stats = correct_stream(
+            &checker,
+            "0001 aple\nnot an entry\n0002 pie\n".as_bytes(),
+            &mut output,
+            &lenient(None),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "0001 apple\n0002 pie\n");
+        assert_eq!(stats.lines, 2);
+        assert_eq!(stats.skipped.len(), 1);
+    }
+}
+
+mod line_numbers {
+    use homework_03::{ParseOptions, WordList};
+
+    fn line_numbers(content: &str, options: &ParseOptions) -> Vec<usize> {
+        WordList::parse_content(content, options)
+            .unwrap()
+            .iter()
+            .map(WordList::line_number)
+            .collect()
+    }
+
+    #[test]
+    fn blank_lines_are_counted() {
+        let content = "0001 one\n\n   \n0002 two\n\n0003 three\n";
+        assert_eq!(line_numbers(content, &ParseOptions::default()), [1, 4, 6]);
+    }
+
+    #[test]
+    fn front_matter_lines_are_counted() {
+        let content = "---\nmax_distance: 2\n---\n0001 one\n\n0002 two\n";
+        assert_eq!(line_numbers(content, &ParseOptions::default()), [4, 6]);
+    }
+
+    #[test]
+    fn skipped_invalid_lines_are_counted() {
+        let options = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        let content = "0001 one\nnot an entry\n0002 two\n";
+        assert_eq!(line_numbers(content, &options), [1, 3]);
+    }
+
+    #[test]
+    fn the_line_number_is_not_written() {
+        let word_list = WordList::parse_line(42, "0001 one two", &ParseOptions::default()).unwrap();
+        assert_eq!(word_list.line_number(), 42);
+        assert_eq!(word_list.to_string(), "0001 one two");
+    }
+}
+
+mod lint {
+    use homework_03::{LintRule, ParseOptions, WordList, lint_content};
+
+    fn violations(content: &str) -> Vec<(usize, usize, LintRule)> {
+        lint_content(content, &ParseOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.line, issue.column, issue.rule))
+            .collect()
+    }
+
+    #[test]
+    fn valid_lines_have_no_violations() {
+        assert!(violations("0001 teh quikc\n\n0002\tthe/cat\n").is_empty());
+    }
+
+    #[test]
+    fn every_violation_on_a_line_is_reported() {
+        assert_eq!(
+            violations("0a1  apple\n"),
+            [
+                (1, 2, LintRule::IdDigits),
+                (1, 4, LintRule::IdLength),
+                (1, 5, LintRule::ExtraSeparator),
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_point_at_the_offending_character() {
+        assert_eq!(
+            violations("0001 ok\n00012 cat\n12\n0003 //\n0004 a\u{7}b\n"),
+            [
+                (2, 5, LintRule::MissingSeparator),
+                (3, 3, LintRule::IdLength),
+                (3, 3, LintRule::MissingSeparator),
+                (3, 3, LintRule::NoWords),
+                (4, 6, LintRule::NoWords),
+                (5, 7, LintRule::ControlCharacter),
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        assert_eq!(
+            violations("0001 caf\u{e9}\u{0}\n"),
+            [(1, 10, LintRule::ControlCharacter)]
+        );
+    }
+
+    #[test]
+    fn overlong_lines_point_past_the_limit() {
+        let options = ParseOptions {
+            max_line_length: Some(8),
+            ..ParseOptions::default()
+        };
+        let issues = lint_content("0001 abcdef\n", &options).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            (issues[0].column, issues[0].rule),
+            (9, LintRule::LineTooLong)
+        );
+    }
+
+    #[test]
+    fn the_caret_sits_under_the_column() {
+        let issues = lint_content("0x01 teh\n", &ParseOptions::default()).unwrap();
+        assert_eq!(
+            issues[0].to_string(),
+            "Line 1, column 2: ID must be ASCII digits, found 'x' [id-digits]\n    0x01 teh\n     ^"
+        );
+    }
+
+    #[test]
+    fn front_matter_is_skipped() {
+        assert_eq!(
+            violations("---\nmax_distance: 2\n---\nbad\n"),
+            [
+                (4, 1, LintRule::IdDigits),
+                (4, 4, LintRule::IdLength),
+                (4, 4, LintRule::MissingSeparator),
+                (4, 4, LintRule::NoWords),
+            ]
+        );
+    }
+
+    #[test]
+    fn variable_width_ids_take_every_digit_before_the_separator() {
+        let options = ParseOptions {
+            variable_width_ids: true,
+            ..ParseOptions::default()
+        };
+        let rules = |content: &str| -> Vec<(usize, usize, LintRule)> {
+            lint_content(content, &options)
+                .unwrap()
+                .into_iter()
+                .map(|issue| (issue.line, issue.column, issue.rule))
+                .collect()
+        };
+
+        assert!(rules("12 cat\n123456 dog\n").is_empty());
+        assert_eq!(rules("1a2 cat\n"), [(1, 2, LintRule::IdDigits)]);
+        // Leading whitespace is skipped, so "cat" is taken as the ID.
+        assert_eq!(
+            rules(" cat\n"),
+            [
+                (1, 2, LintRule::IdDigits),
+                (1, 5, LintRule::MissingSeparator),
+                (1, 5, LintRule::NoWords),
+            ]
+        );
+    }
+
+    #[test]
+    fn whitespace_around_an_entry_is_ignored_like_when_parsing() {
+        let content = "  0001 teh \n\t0002 cat\t\n";
+        assert!(WordList::validate_content(content, &ParseOptions::default()).is_ok());
+        assert!(violations(content).is_empty());
+    }
+
+    #[test]
+    fn columns_count_from_the_start_of_an_indented_line() {
+        assert_eq!(violations("  0a01 cat\n"), [(1, 4, LintRule::IdDigits)]);
+    }
+}
+
+mod merge_separators {
+    use crate::common;
+    use homework_03::{ParseOptions, SeparatorMerge, WordList};
+
+    fn merged(line: &str, keep: SeparatorMerge) -> String {
+        let mut word_list = WordList::parse_content(line, &ParseOptions::default())
+            .unwrap()
+            .remove(0);
+        word_list.merge_separators(keep);
+        word_list.to_string()
+    }
+
+    #[test]
+    fn double_spaces_collapse_to_one() {
+        assert_eq!(
+            merged("0001 apple  banana   cherry", SeparatorMerge::First),
+            "0001 apple banana cherry"
+        );
+    }
+
+    #[test]
+    fn mixed_runs_keep_the_configured_separator() {
+        let line = "0001 apple /banana/ cherry";
+        assert_eq!(
+            merged(line, SeparatorMerge::First),
+            "0001 apple banana/cherry"
+        );
+        assert_eq!(
+            merged(line, SeparatorMerge::Last),
+            "0001 apple/banana cherry"
+        );
+    }
+
+    #[test]
+    fn single_separators_are_unchanged() {
+        let line = "0001 apple/banana cherry";
+        assert_eq!(merged(line, SeparatorMerge::First), line);
+        assert_eq!(merged(line, SeparatorMerge::Last), line);
+    }
+
+    #[test]
+    fn runs_left_by_dropped_words_are_merged() {
+        let checker = common::checker(&["apple", "banana"])
+            .with_max_distance(1)
+            .with_drop_unknown(true);
+        let word_lists =
+            WordList::parse_content("0001 apple  xyzzy  banana\n", &ParseOptions::default())
+                .unwrap();
+
+        let mut corrected = checker.correct_word_list(&word_lists[0]);
+        corrected.merge_separators(SeparatorMerge::First);
+        assert_eq!(corrected.to_string(), "0001 apple banana");
+    }
+}
+
+mod separator_modes {
+    use crate::common;
+    use homework_03::{ParseOptions, SeparatorMode, Token, WordList};
+
+    fn options(mode: SeparatorMode) -> ParseOptions {
+        let mut options = ParseOptions::default();
+        options.set_separator('/', mode);
+        options
+    }
+
+    fn correct(line: &str, options: &ParseOptions) -> String {
+        let checker = common::checker(&["and", "or", "the"]).with_max_distance(2);
+        let word_list = WordList::parse_line(1, line, options).unwrap();
+        checker.correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn slash_splits_by_default() {
+        let word_list = WordList::parse_line(1, "0001 and/or", &ParseOptions::default()).unwrap();
+        assert_eq!(
+            word_list.tokens,
+            [
+                Token::Word("and".to_string()),
+                Token::Separator('/'),
+                Token::Word("or".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_mode_corrects_each_side() {
+        assert_eq!(
+            correct("0001 adn/orr", &options(SeparatorMode::Split)),
+            "0001 and/or"
+        );
+    }
+
+    #[test]
+    fn keep_mode_leaves_the_slash_inside_the_token() {
+        let word_list =
+            WordList::parse_line(1, "0001 12/25", &options(SeparatorMode::Keep)).unwrap();
+        assert_eq!(word_list.tokens, [Token::Word("12/25".to_string())]);
+        assert_eq!(
+            correct("0001 12/25 teh", &options(SeparatorMode::Keep)),
+            "0001 12/25 the"
+        );
+    }
+
+    #[test]
+    fn setting_a_mode_replaces_the_earlier_one() {
+        let mut options = options(SeparatorMode::Keep);
+        options.set_separator('/', SeparatorMode::Split);
+        assert!(options.is_separator('/'));
+        assert_eq!(
+            options.separators.iter().filter(|(c, _)| *c == '/').count(),
+            1
+        );
+    }
+}
+
+mod tags {
+    use crate::common;
+    use homework_03::{ParseOptions, Token, WordList};
+
+    fn options(preserve_tags: bool) -> ParseOptions {
+        ParseOptions {
+            preserve_tags,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn correct(line: &str) -> String {
+        let checker = common::checker(&["a", "brown", "quick", "the"]);
+        let word_list = WordList::parse_line(1, line, &options(true)).unwrap();
+        checker.correct_word_list(&word_list).to_string()
+    }
+
+    #[test]
+    fn a_wrapped_word_is_corrected() {
+        assert_eq!(correct("0001 <b>teh</b>"), "0001 <b>the</b>");
+    }
+
+    #[test]
+    fn tags_before_and_after_words() {
+        assert_eq!(correct("0001 <i>quikc brwn"), "0001 <i>quick brown");
+        assert_eq!(correct("0001 quikc brwn</i>"), "0001 quick brown</i>");
+    }
+
+    #[test]
+    fn attributes_are_kept_verbatim() {
+        assert_eq!(
+            correct("0001 <a href=\"x y\">teh</a> brwn"),
+            "0001 <a href=\"x y\">the</a> brown"
+        );
+    }
+
+    #[test]
+    fn tags_are_single_tokens() {
+        let word_list = WordList::parse_line(1, "0001 <b>teh</b>", &options(true)).unwrap();
+        assert_eq!(
+            word_list.tokens,
+            [
+                Token::Delimiter("<b>".to_string()),
+                Token::Word("teh".to_string()),
+                Token::Delimiter("</b>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn angle_brackets_that_are_not_tags_stay_in_words() {
+        let word_list = WordList::parse_line(1, "0001 a<3 <b", &options(true)).unwrap();
+        assert!(
+            word_list
+                .tokens
+                .iter()
+                .all(|token| !matches!(token, Token::Delimiter(_)))
+        );
+    }
+
+    #[test]
+    fn plain_text_is_not_affected_by_default() {
+        let word_list = WordList::parse_line(1, "0001 <b>teh<br>", &options(false)).unwrap();
+        assert_eq!(word_list.tokens, [Token::Word("<b>teh<br>".to_string())]);
+    }
+}
+
+mod typography {
+    use crate::common;
+    use homework_03::{
+        ParseOptions, TypographyPolicy, WordList, normalize_typography, restore_typography,
+    };
+
+    const WORDS: &[&str] = &["don't", "i'm", "re-enter", "said"];
+
+    fn correct(line: &str, typography: TypographyPolicy) -> String {
+        let options = ParseOptions {
+            typography,
+            ..ParseOptions::default()
+        };
+        let word_list = WordList::parse_line(1, line, &options).unwrap();
+        common::checker(WORDS)
+            .correct_word_list(&word_list)
+            .to_string()
+    }
+
+    #[test]
+    fn curly_apostrophes_match_straight_ones() {
+        assert_eq!(
+            correct("0001 don\u{2019}t", TypographyPolicy::Normalize),
+            "0001 don't"
+        );
+        assert!(common::checker(WORDS).contains_word(&normalize_typography("don\u{2019}t")));
+    }
+
+    #[test]
+    fn restore_keeps_the_characters_as_written() {
+        assert_eq!(
+            correct("0001 don\u{2019}t said", TypographyPolicy::Restore),
+            "0001 don\u{2019}t said"
+        );
+        assert_eq!(
+            correct(
+                "0001 dont\u{2019} re\u{2013}entr",
+                TypographyPolicy::Restore
+            ),
+            "0001 don\u{2019}t re\u{2013}enter"
+        );
+    }
+
+    #[test]
+    fn without_normalization_the_curly_form_is_a_different_word() {
+        assert_ne!(
+            correct("0001 don\u{2019}t", TypographyPolicy::Keep),
+            "0001 don\u{2019}t"
+        );
+    }
+
+    #[test]
+    fn quotes_and_dashes_map_to_ascii() {
+        assert_eq!(
+            normalize_typography("\u{201c}a\u{201d} \u{2018}b\u{2019} c\u{2014}d"),
+            "\"a\" 'b' c-d"
+        );
+    }
+
+    #[test]
+    fn restoring_follows_the_order_of_the_original() {
+        assert_eq!(
+            restore_typography("\u{201c}teh\u{201d}", "\"the\""),
+            "\u{201c}the\u{201d}"
+        );
+        assert_eq!(restore_typography("plain", "plane"), "plane");
+    }
+}
+
+mod variable_width_ids {
+    use homework_03::{ParseOptions, WordList};
+
+    fn options() -> ParseOptions {
+        ParseOptions {
+            variable_width_ids: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn ids_of_any_width_are_read_up_to_the_separator() {
+        for (line, id) in [
+            ("12 word", "12"),
+            ("0001 word", "0001"),
+            ("123456 word", "123456"),
+        ] {
+            let word_list = WordList::parse_line(1, line, &options()).unwrap();
+            assert_eq!(word_list.id, id);
+            assert_eq!(word_list.to_string(), line);
+        }
+    }
+
+    #[test]
+    fn tabs_and_collapsing_still_apply() {
+        let word_list = WordList::parse_line(1, "123456\t\tword", &options()).unwrap();
+        assert_eq!(word_list.id_separator, "\t\t");
+
+        let collapsing = ParseOptions {
+            collapse_id_separator: true,
+            ..options()
+        };
+        let word_list = WordList::parse_line(1, "12   word", &collapsing).unwrap();
+        assert_eq!(word_list.to_string(), "12 word");
+    }
+
+    #[test]
+    fn ids_must_be_digits() {
+        assert!(WordList::parse_line(1, "12a word", &options()).is_err());
+        assert!(WordList::parse_line(1, "word", &options()).is_err());
+        assert!(WordList::parse_line(1, "12", &options()).is_err());
+    }
+
+    #[test]
+    fn fixed_width_is_the_default() {
+        let options = ParseOptions::default();
+        assert!(WordList::parse_line(1, "12 word", &options).is_err());
+        assert!(WordList::parse_line(1, "123456 word", &options).is_err());
+        assert_eq!(
+            WordList::parse_line(1, "0001 word", &options).unwrap().id,
+            "0001"
+        );
+    }
+}